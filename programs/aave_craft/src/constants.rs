@@ -3,3 +3,27 @@ pub const INVESTMENT_DAO_TREASURY_SEED: &[u8] = b"investment_dao_treasury";
 pub const DAO_PROPOSAL_SEED: &[u8] = b"dao_proposal";
 pub const WITHDRWAL_SEED: &[u8] = b"withdrwal";
 pub const VESTING_SEED: &[u8] = b"vesting";
+pub const DAO_GOVERNANCE_SEED: &[u8] = b"dao_governance";
+pub const DAO_SHARE_MINT_SEED: &[u8] = b"dao_share_mint";
+pub const TREASURY_ASSET_SEED: &[u8] = b"treasury_asset";
+pub const DAO_NAME_SEED: &[u8] = b"dao_name";
+pub const MILESTONE_VOTE_SEED: &[u8] = b"milestone_vote";
+//Needs to match max_len of InvestorFinancialRecord::checkpoints
+pub const MAX_DEPOSIT_CHECKPOINTS: usize = 32;
+//Needs to match max_len of InvestmentDao::live_withdrawals. Each live withdrawal blocks collapsing of at most two
+//checkpoints, so it has to stay below half of MAX_DEPOSIT_CHECKPOINTS
+pub const MAX_LIVE_WITHDRAWALS: usize = 8;
+//Time members have to claim executed withdrawal, afterwards unclaimed funds can be returned to treasury
+pub const WITHDRAWAL_CLAIM_PERIOD: i64 = 30 * 24 * 60 * 60;
+//Needs to match max_len of InvestmentDao::name and DaoName::name (in bytes)
+pub const MAX_DAO_NAME_LEN: usize = 64;
+//Needs to match max_len of Proposal::options
+pub const MAX_PROPOSAL_OPTIONS: usize = 5;
+//Needs to match max_len of VestingSchedule::UnlockTable::checkpoints
+pub const MAX_UNLOCK_CHECKPOINTS: usize = 10;
+//Needs to match max_len of VestingSchedule::Milestones::milestones
+pub const MAX_MILESTONES: usize = 5;
+//Needs to match max_len of Milestone::name
+pub const MAX_MILESTONE_NAME_LEN: usize = 20;
+//Every registered asset is passed on member exits, so count is limited by transaction size
+pub const MAX_TREASURY_ASSETS: u8 = 4;
@@ -30,4 +30,102 @@ pub enum InvestmentDaoError {
     InvalidVestingAuthority,
     #[msg("No tokens left to claim")]
     AllTokensClaimed,
+    #[msg("Voting period has ended")]
+    VotingPeriodEnded,
+    #[msg("Voting period has not ended yet")]
+    VotingPeriodNotEnded,
+    #[msg("Invalid proposal authority")]
+    InvalidProposalAuthority,
+    #[msg("Veto window has passed")]
+    VetoWindowPassed,
+    #[msg("Veto window has not passed yet")]
+    VetoWindowNotPassed,
+    #[msg("Voter weight not available for proposal snapshot")]
+    VoterWeightUnavailable,
+    #[msg("Invalid vote record")]
+    InvalidVoteRecord,
+    #[msg("Invalid vote option")]
+    InvalidVoteOption,
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Deposit amount too small to mint shares")]
+    DepositTooSmall,
+    #[msg("Invalid share mint")]
+    InvalidShareMint,
+    #[msg("Invalid share transfer")]
+    InvalidShareTransfer,
+    #[msg("Invalid treasury asset")]
+    InvalidTreasuryAsset,
+    #[msg("Invalid fundraising config")]
+    InvalidFundraisingConfig,
+    #[msg("Fundraising round is not open yet")]
+    FundraisingNotOpen,
+    #[msg("Fundraising round is closed")]
+    FundraisingClosed,
+    #[msg("Deposit amount is below minimal ticket")]
+    DepositBelowMinimum,
+    #[msg("Deposit exceeds maximal investor ticket")]
+    MaxTicketExceeded,
+    #[msg("Deposit exceeds DAO hard cap")]
+    HardCapExceeded,
+    #[msg("Deposit exceeds investor allocation")]
+    AllocationExceeded,
+    #[msg("Invitation has expired")]
+    InvitationExpired,
+    #[msg("DAO has no allowlist")]
+    AllowlistNotSet,
+    #[msg("Invalid allowlist proof")]
+    InvalidAllowlistProof,
+    #[msg("DAO membership policy doesn't allow joining")]
+    JoinNotAllowed,
+    #[msg("Invalid membership gate account")]
+    InvalidGateAccount,
+    #[msg("Invitation is not pending")]
+    InvitationNotPending,
+    #[msg("Invalid DAO name")]
+    InvalidDaoName,
+    #[msg("Invalid vesting config")]
+    InvalidVestingConfig,
+    #[msg("Vesting config is missing")]
+    MissingVestingConfig,
+    #[msg("Withdraw amount is missing")]
+    MissingWithdrawAmount,
+    #[msg("Invalid withdraw amount")]
+    InvalidWithdrawAmount,
+    #[msg("Insufficient treasury balance")]
+    InsufficientTreasuryBalance,
+    #[msg("Vesting is already revoked")]
+    VestingAlreadyRevoked,
+    #[msg("Invalid milestone")]
+    InvalidMilestone,
+    #[msg("Milestone can't be approved this way")]
+    InvalidMilestoneApproval,
+    #[msg("Milestone is already approved")]
+    MilestoneAlreadyApproved,
+    #[msg("DAO has shares but no assets, deposit can't be priced")]
+    DepositNotPriceable,
+    #[msg("Maximal number of treasury assets is registered")]
+    TreasuryAssetsLimitReached,
+    #[msg("All registered treasury assets need to be passed")]
+    MissingTreasuryAssets,
+    #[msg("Too many executed withdrawals can still be claimed")]
+    TooManyLiveWithdrawals,
+    #[msg("Share checkpoint history is full")]
+    CheckpointHistoryFull,
+    #[msg("Withdrawal claim period has ended")]
+    WithdrawalClaimPeriodEnded,
+    #[msg("Withdrawal claim period has not ended yet")]
+    WithdrawalClaimPeriodNotEnded,
+    #[msg("Vote supports proposal that is not executed yet")]
+    SupportingVoteLocked,
+    #[msg("Member needs to relinquish supporting votes first")]
+    MemberHasSupportingVotes,
+    #[msg("Authority action can be proposed only in governed DAO")]
+    DaoNotGoverned,
+    #[msg("Member has no voter weight at vote snapshot")]
+    NoVoterWeight,
+    #[msg("Milestone vote is still open")]
+    MilestoneVoteNotResolved,
 }
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+//Invitation accounts are closed on rejection and revocation, so events let clients tell them apart
+
+#[event]
+pub struct InvitationRejected {
+    pub dao: Pubkey,
+    pub investor: Pubkey,
+    pub rejected_at: i64,
+}
+
+#[event]
+pub struct InvitationRevoked {
+    pub dao: Pubkey,
+    pub investor: Pubkey,
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub dao: Pubkey,
+    pub vesting: Pubkey,
+    pub milestone_index: u8,
+    pub approved_at: i64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub dao: Pubkey,
+    pub vesting: Pubkey,
+    //Unvested amount sent back to DAO treasury
+    pub returned_amount: u64,
+    pub revoked_at: i64,
+}
+
+#[event]
+pub struct MemberRagequit {
+    pub dao: Pubkey,
+    pub investor: Pubkey,
+    pub shares: u64,
+    //Paid out in denominated currency
+    pub payout: u64,
+    pub exited_at: i64,
+}
+
+#[event]
+pub struct WithdrawalClosed {
+    pub dao: Pubkey,
+    pub proposal: Pubkey,
+    //Unclaimed amount sent back to DAO treasury
+    pub returned_amount: u64,
+    pub closed_at: i64,
+}
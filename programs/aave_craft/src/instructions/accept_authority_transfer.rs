@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::InvestmentDaoError, state::InvestmentDao};
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    ///CHECK: needs to sign, unless it is governance PDA of DAO
+    pub new_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+}
+
+pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+    let new_authority = &ctx.accounts.new_authority;
+
+    require!(
+        investment_dao.pending_authority == Some(new_authority.key()),
+        InvestmentDaoError::InvalidPendingAuthority
+    );
+
+    let (governance_authority, _) =
+        InvestmentDao::get_governance_authority(&investment_dao.key(), ctx.program_id);
+
+    //Governance PDA can't sign, it is controlled only by DAO proposals
+    require!(
+        new_authority.is_signer || new_authority.key() == governance_authority,
+        InvestmentDaoError::InvalidPendingAuthority
+    );
+
+    investment_dao.authority = new_authority.key();
+    investment_dao.pending_authority = None;
+
+    Ok(())
+}
@@ -3,21 +3,21 @@ use anchor_lang::prelude::*;
 use crate::{
     constants::INVESTMENT_DAO_SEED,
     errors::InvestmentDaoError,
+    events::InvitationRejected,
     state::{InvestmentDao, InvestorData, InvestorState, InvitationAction},
 };
 
 #[derive(Accounts)]
 pub struct AcceptDaoInvitation<'info> {
     #[account(mut)]
-    ///CHECK: checked with has_one constraint
-    pub authority: UncheckedAccount<'info>,
+    pub investor: Signer<'info>,
     #[account(mut)]
-    ///CHECK: checked in seeds of investor_data
-    pub investor: UncheckedAccount<'info>,
-    #[account(has_one=authority)]
     pub investment_dao: Box<Account<'info, InvestmentDao>>,
     #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
     pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,address=investor_data.invited_by)]
+    ///CHECK: rent receiver of rejected invitation, checked with address constraint
+    pub inviter: UncheckedAccount<'info>,
 }
 
 pub fn accept_or_reject_dao_invitation(
@@ -32,14 +32,32 @@ pub fn accept_or_reject_dao_invitation(
         InvestmentDaoError::InvalidInvestorWallet
     );
 
+    //Invitation can be resolved only once
+    require!(
+        investor_data.state == InvestorState::Invited,
+        InvestmentDaoError::InvitationNotPending
+    );
+
     match action {
         InvitationAction::Accept => {
+            require!(
+                !investor_data.is_invitation_expired(Clock::get().unwrap().unix_timestamp),
+                InvestmentDaoError::InvitationExpired
+            );
+
             investor_data.joined_at = Clock::get().unwrap().unix_timestamp;
             investor_data.state = InvestorState::Accepted;
             investment_dao.investors_count = investment_dao.investors_count.checked_add(1).unwrap();
         }
         InvitationAction::Reject => {
-            investor_data.close(ctx.accounts.authority.to_account_info())?;
+            //Rent is returned to inviter, same as on revocation
+            investor_data.close(ctx.accounts.inviter.to_account_info())?;
+
+            emit!(InvitationRejected {
+                dao: investment_dao.key(),
+                investor: ctx.accounts.investor.key(),
+                rejected_at: Clock::get().unwrap().unix_timestamp,
+            });
         }
     }
 
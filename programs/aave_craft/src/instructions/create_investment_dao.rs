@@ -2,35 +2,51 @@ use anchor_lang::{
     prelude::*,
     system_program::{create_account, CreateAccount},
 };
-use anchor_spl::token::{InitializeAccount, Token, TokenAccount};
+use anchor_spl::{
+    token::{InitializeMint, Mint, Token},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
-    constants::INVESTMENT_DAO_SEED,
-    errors::InvestmentDaoError,
-    state::{Currency, Governance, InvestmentDao, InvestorData, InvestorState},
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_SEED, INVESTMENT_DAO_TREASURY_SEED},
+    state::{
+        Currency, Fundraising, Governance, InvestmentDao, InvestorData, InvestorState,
+        MembershipPolicy,
+    },
+    utils::create_treasury_token_account,
 };
 
 #[derive(Accounts)]
-#[instruction(name:String)]
+#[instruction(nonce:u64)]
 pub struct CreateInvestmentDao<'info> {
     #[account(mut)]
     pub dao_authority: Signer<'info>,
-    #[account(init,seeds=[INVESTMENT_DAO_SEED,name.as_bytes()],bump,space=8+InvestmentDao::INIT_SPACE,payer=dao_authority)]
+    #[account(init,seeds=[INVESTMENT_DAO_SEED,dao_authority.key().as_ref(),&nonce.to_le_bytes()],bump,space=8+InvestmentDao::INIT_SPACE,payer=dao_authority)]
     pub investment_dao: Box<Account<'info, InvestmentDao>>,
     ///CHECK: checked in ix
     pub denominated_currency: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    //Token program of denominated currency (SPL Token or Token-2022)
+    pub currency_token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
     #[account(init,payer=dao_authority,space=8+InvestorData::INIT_SPACE
         ,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),dao_authority.key().as_ref()],bump)]
     pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    ///CHECK: created in ix
+    pub share_mint: UncheckedAccount<'info>,
 }
 
-pub fn create_investment_dao<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, CreateInvestmentDao<'info>>,
+#[allow(clippy::too_many_arguments)]
+pub fn create_investment_dao<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateInvestmentDao<'info>>,
+    nonce: u64,
     name: String,
     governance_config: Governance,
+    fundraising_config: Fundraising,
+    allowlist_root: Option<[u8; 32]>,
+    membership_policy: MembershipPolicy,
 ) -> Result<()> {
     let investment_dao = &mut ctx.accounts.investment_dao;
 
@@ -41,16 +57,27 @@ pub fn create_investment_dao<'a, 'b, 'c, 'info>(
     investor_data.invited_at = Clock::get().unwrap().unix_timestamp;
     investor_data.joined_at = Clock::get().unwrap().unix_timestamp;
     investor_data.state = InvestorState::Accepted;
+    investor_data.allocation = None;
+    investor_data.expires_at = None;
+    investor_data.invited_by = ctx.accounts.dao_authority.key();
+
+    InvestmentDao::validate_name(&name)?;
 
     investment_dao.authority = ctx.accounts.dao_authority.key();
+    investment_dao.creator = ctx.accounts.dao_authority.key();
+    investment_dao.nonce = nonce;
+    investment_dao.pending_authority = None;
     investment_dao.denominated_currency = ctx.accounts.denominated_currency.key();
 
-    require!(
-        governance_config.voting_quorum <= 100,
-        InvestmentDaoError::InvalidGovernanceConfig
-    );
+    governance_config.validate()?;
 
     investment_dao.governance_config = governance_config;
+
+    fundraising_config.validate()?;
+
+    investment_dao.fundraising_config = fundraising_config;
+    investment_dao.allowlist_root = allowlist_root;
+    investment_dao.membership_policy = membership_policy;
     let remaining_accounts = &mut ctx.remaining_accounts.iter();
     if ctx.accounts.denominated_currency.key() == Pubkey::default() {
         //If denominated currency is 111..111, we consider dao currency Solana
@@ -60,39 +87,75 @@ pub fn create_investment_dao<'a, 'b, 'c, 'info>(
 
         let dao_treasury = next_account_info(remaining_accounts)?;
 
-        InvestmentDao::check_treasury_seeds(
+        let treasury_bump = InvestmentDao::check_treasury_seeds(
             dao_treasury,
             &investment_dao.key(),
             ctx.accounts.denominated_currency.key(),
-            ctx.accounts.token_program.key,
+            ctx.program_id,
         )?;
 
-        //If currency is token, we need to create treasury token account
-        create_account(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                CreateAccount {
-                    from: ctx.accounts.dao_authority.to_account_info(),
-                    to: dao_treasury.to_account_info(),
-                },
-            ),
-            Rent::default().minimum_balance(TokenAccount::LEN),
-            TokenAccount::LEN as u64,
-            ctx.accounts.token_program.key,
+        //Authority of token account is token account itself (that way nobody can control it but smart contract logic)
+        create_treasury_token_account(
+            &ctx.accounts.dao_authority.to_account_info(),
+            dao_treasury,
+            &ctx.accounts.denominated_currency.to_account_info(),
+            &ctx.accounts.currency_token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[&[
+                INVESTMENT_DAO_TREASURY_SEED,
+                investment_dao.key().as_ref(),
+                ctx.accounts.denominated_currency.key().as_ref(),
+                &[treasury_bump],
+            ]],
         )?;
+    }
 
-        //Authority of token account is token account itself (that way nobody can control it but smart contract logic)
-        anchor_spl::token::initialize_account(CpiContext::new(
+    //Shares have same decimals as denominated currency (SOL has 9 decimals)
+    let share_decimals = if investment_dao.currency == Currency::Sol {
+        9
+    } else {
+        InterfaceAccount::<token_interface::Mint>::try_from(
+            &ctx.accounts.denominated_currency.to_account_info(),
+        )?
+        .decimals
+    };
+
+    let share_mint_bump = *ctx.bumps.get(&"share_mint".to_string()).unwrap();
+
+    create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.dao_authority.to_account_info(),
+                to: ctx.accounts.share_mint.to_account_info(),
+            },
+            &[&[
+                DAO_SHARE_MINT_SEED,
+                investment_dao.key().as_ref(),
+                &[share_mint_bump],
+            ]],
+        ),
+        Rent::default().minimum_balance(Mint::LEN),
+        Mint::LEN as u64,
+        ctx.accounts.token_program.key,
+    )?;
+
+    //Share mint is both mint and freeze authority of itself
+    anchor_spl::token::initialize_mint(
+        CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            InitializeAccount {
-                account: dao_treasury.to_account_info(),
-                authority: dao_treasury.to_account_info(),
-                mint: ctx.accounts.denominated_currency.to_account_info(),
+            InitializeMint {
+                mint: ctx.accounts.share_mint.to_account_info(),
                 rent: ctx.accounts.rent.to_account_info(),
             },
-        ))?;
-    }
+        ),
+        share_decimals,
+        ctx.accounts.share_mint.key,
+        Some(ctx.accounts.share_mint.key),
+    )?;
 
+    investment_dao.share_mint = ctx.accounts.share_mint.key();
+    investment_dao.total_shares = 0;
     investment_dao.investors_count = 0;
     investment_dao.total_deposits_count = 0;
     investment_dao.name = name;
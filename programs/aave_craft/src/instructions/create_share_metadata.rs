@@ -0,0 +1,70 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::token::Mint;
+use mpl_token_metadata::{instruction::create_metadata_accounts_v3, pda::find_metadata_account};
+
+use crate::{constants::DAO_SHARE_MINT_SEED, errors::InvestmentDaoError, state::InvestmentDao};
+
+#[derive(Accounts)]
+pub struct CreateShareMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    #[account(seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    pub share_mint: Box<Account<'info, Mint>>,
+    #[account(mut,address=find_metadata_account(&share_mint.key()).0)]
+    ///CHECK: address checked, created by token metadata program
+    pub metadata: UncheckedAccount<'info>,
+    #[account(address=mpl_token_metadata::ID)]
+    ///CHECK: address checked
+    pub token_metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_share_metadata(
+    ctx: Context<CreateShareMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let share_mint = &ctx.accounts.share_mint;
+    let investment_dao_key = ctx.accounts.investment_dao.key();
+
+    //Share mint signs as mint authority and stays update authority of metadata
+    invoke_signed(
+        &create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            ctx.accounts.metadata.key(),
+            share_mint.key(),
+            share_mint.key(),
+            ctx.accounts.authority.key(),
+            share_mint.key(),
+            name,
+            symbol,
+            uri,
+            None,
+            0,
+            true,
+            true,
+            None,
+            None,
+            None,
+        ),
+        &[
+            ctx.accounts.metadata.to_account_info(),
+            share_mint.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        &[&[
+            DAO_SHARE_MINT_SEED,
+            investment_dao_key.as_ref(),
+            &[*ctx.bumps.get(&"share_mint".to_string()).unwrap()],
+        ]],
+    )?;
+
+    Ok(())
+}
@@ -1,13 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
-    constants::{INVESTMENT_DAO_SEED, INVESTMENT_DAO_TREASURY_SEED},
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_SEED, INVESTMENT_DAO_TREASURY_SEED},
     errors::InvestmentDaoError,
+    math::mul_div_floor,
     state::{
         investment_dao::{Currency, InvestorFinancialRecord, InvestorState},
         InvestmentDao, InvestorData,
     },
+    utils::{mint_shares, token_balance, transfer_currency, treasury_assets_value},
 };
 
 #[derive(Accounts)]
@@ -23,39 +28,39 @@ pub struct DepositFunds<'info> {
     pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
     #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
     pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    pub share_mint: Box<Account<'info, Mint>>,
+    #[account(mut,associated_token::mint=share_mint,associated_token::authority=investor)]
+    pub investor_share_account: Box<Account<'info, TokenAccount>>,
     pub token_program: Program<'info, Token>,
+    pub currency_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn deposit_funds<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, DepositFunds<'info>>,
+pub fn deposit_funds<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositFunds<'info>>,
     amount: u64,
 ) -> Result<()> {
-    let investment_dao = &mut ctx.accounts.investment_dao;
-
-    investment_dao.total_deposited = investment_dao.total_deposited.checked_add(amount).unwrap();
-    investment_dao.total_deposits_count =
-        investment_dao.total_deposits_count.checked_add(1).unwrap();
-
-    let financial_record = &mut ctx.accounts.investor_financial_record;
-
     //check that only dao member can deposit funds
     require!(
         ctx.accounts.investor_data.state == InvestorState::Accepted,
         InvestmentDaoError::NotPartOfDao
     );
 
-    financial_record.authority = ctx.accounts.investor.key();
-    financial_record.last_deposit_at = Clock::get().unwrap().unix_timestamp;
+    let investment_dao = &mut ctx.accounts.investment_dao;
 
-    financial_record.total_deposit_amount = financial_record
-        .total_deposit_amount
-        .checked_add(amount)
-        .unwrap();
+    investment_dao
+        .fundraising_config
+        .check_deposit(amount, Clock::get().unwrap().unix_timestamp)?;
+
+    let treasury_balance = match investment_dao.currency {
+        Currency::Sol => ctx.accounts.dao_treasury.lamports(),
+        Currency::Spl => token_balance(&ctx.accounts.dao_treasury.to_account_info())?,
+    };
 
     let remaining_accounts = &mut ctx.remaining_accounts.iter();
 
-    match investment_dao.currency {
+    let received_amount = match investment_dao.currency {
         Currency::Sol => {
             anchor_lang::system_program::transfer(
                 CpiContext::new(
@@ -67,32 +72,104 @@ pub fn deposit_funds<'a, 'b, 'c, 'info>(
                 ),
                 amount,
             )?;
+
+            amount
         }
         Currency::Spl => {
             let raw_investor_token = next_account_info(remaining_accounts)?;
+            let currency_mint = next_account_info(remaining_accounts)?;
 
-            let investor_token = Account::<TokenAccount>::try_from(raw_investor_token)?;
+            let investor_token =
+                InterfaceAccount::<token_interface::TokenAccount>::try_from(raw_investor_token)?;
 
             require!(
-                investor_token.mint == investment_dao.denominated_currency,
+                investor_token.mint == investment_dao.denominated_currency
+                    && currency_mint.key() == investment_dao.denominated_currency,
                 InvestmentDaoError::InvalidDepositMint
             );
 
-            //We deserialize dao_treasury as additional check to confirm it is token account
-            Account::<TokenAccount>::try_from(&ctx.accounts.dao_treasury.to_account_info())?;
-
-            anchor_spl::token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    anchor_spl::token::Transfer {
-                        authority: ctx.accounts.investor.to_account_info(),
-                        from: raw_investor_token.to_account_info(),
-                        to: ctx.accounts.dao_treasury.to_account_info(),
-                    },
-                ),
+            transfer_currency(
+                raw_investor_token,
+                &ctx.accounts.dao_treasury.to_account_info(),
+                &ctx.accounts.investor.to_account_info(),
+                currency_mint,
+                &ctx.accounts.currency_token_program.to_account_info(),
                 amount,
+                &[],
             )?;
+
+            //Token-2022 transfer fees are withheld in treasury account, so only received amount is credited
+            token_balance(&ctx.accounts.dao_treasury.to_account_info())?
+                .checked_sub(treasury_balance)
+                .unwrap()
         }
-    }
+    };
+
+    //Registered treasury assets follow currency accounts
+    let treasury_assets_value =
+        treasury_assets_value(investment_dao, remaining_accounts.as_slice())?;
+
+    let net_asset_value =
+        investment_dao.net_asset_value(treasury_balance, treasury_assets_value)?;
+
+    //Shares of DAO without any value can't be priced, so depositor would take over existing members
+    require!(
+        investment_dao.total_shares == 0 || net_asset_value > 0,
+        InvestmentDaoError::DepositNotPriceable
+    );
+
+    //Shares are minted at current NAV per share (1:1 for first deposit), rounded down in favor of DAO
+    let shares = if investment_dao.total_shares == 0 {
+        received_amount
+    } else {
+        mul_div_floor(
+            received_amount,
+            investment_dao.total_shares,
+            net_asset_value,
+        )?
+    };
+
+    require!(shares > 0, InvestmentDaoError::DepositTooSmall);
+
+    investment_dao.total_shares = investment_dao.total_shares.checked_add(shares).unwrap();
+    investment_dao.total_deposited = investment_dao
+        .total_deposited
+        .checked_add(received_amount)
+        .unwrap();
+    investment_dao.total_deposits_count =
+        investment_dao.total_deposits_count.checked_add(1).unwrap();
+
+    let financial_record = &mut ctx.accounts.investor_financial_record;
+
+    let clock = Clock::get().unwrap();
+
+    financial_record.authority = ctx.accounts.investor.key();
+    financial_record.last_deposit_at = clock.unix_timestamp;
+
+    financial_record.total_deposit_amount = financial_record
+        .total_deposit_amount
+        .checked_add(received_amount)
+        .unwrap();
+    financial_record.shares = financial_record.shares.checked_add(shares).unwrap();
+    financial_record.write_checkpoint(&clock, &investment_dao.live_withdrawals)?;
+
+    investment_dao.fundraising_config.check_caps(
+        investment_dao.total_deposited,
+        financial_record.total_deposit_amount,
+    )?;
+    ctx.accounts
+        .investor_data
+        .check_allocation(financial_record.total_deposit_amount)?;
+
+    mint_shares(
+        investment_dao,
+        &ctx.accounts.share_mint.to_account_info(),
+        *ctx.bumps.get(&"share_mint".to_string()).unwrap(),
+        &ctx.accounts.investor_share_account,
+        &ctx.accounts.investor.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        shares,
+    )?;
+
     Ok(())
 }
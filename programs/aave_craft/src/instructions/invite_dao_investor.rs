@@ -20,7 +20,11 @@ pub struct InviteDaoInvestor<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn invite_dao_investor(ctx: Context<InviteDaoInvestor>) -> Result<()> {
+pub fn invite_dao_investor(
+    ctx: Context<InviteDaoInvestor>,
+    allocation: Option<u64>,
+    expires_at: Option<i64>,
+) -> Result<()> {
     let dao_investor = &mut ctx.accounts.dao_investor;
     let investment_dao = &ctx.accounts.investment_dao;
 
@@ -36,5 +40,13 @@ pub fn invite_dao_investor(ctx: Context<InviteDaoInvestor>) -> Result<()> {
     dao_investor.invited_at = Clock::get().unwrap().unix_timestamp;
     dao_investor.joined_at = 0;
     dao_investor.state = InvestorState::Invited;
+    dao_investor.allocation = allocation;
+    dao_investor.expires_at = expires_at;
+    dao_investor.invited_by = authority;
+
+    require!(
+        !dao_investor.is_invitation_expired(dao_investor.invited_at),
+        InvestmentDaoError::InvitationExpired
+    );
     Ok(())
 }
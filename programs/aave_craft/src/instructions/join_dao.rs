@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use mpl_token_metadata::{
+    pda::find_metadata_account,
+    state::{Metadata, TokenMetadataAccount},
+};
+
+use crate::{
+    constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, InvestorData, InvestorState, MembershipPolicy},
+};
+
+#[derive(Accounts)]
+pub struct JoinDao<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(mut)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    #[account(init,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump,payer=investor,space=8+InvestorData::INIT_SPACE)]
+    pub investor_data: Box<Account<'info, InvestorData>>,
+    pub system_program: Program<'info, System>,
+}
+
+//Joins open or token gated DAO. For gated DAOs, investor token account is passed in remaining accounts,
+//followed by NFT metadata account for collection gated DAOs
+pub fn join_dao<'info>(ctx: Context<'_, '_, '_, 'info, JoinDao<'info>>) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    match investment_dao.membership_policy {
+        MembershipPolicy::InviteOnly => return Err(error!(InvestmentDaoError::JoinNotAllowed)),
+        MembershipPolicy::Open => {}
+        MembershipPolicy::TokenGated { mint, min_balance } => {
+            let investor_token =
+                InterfaceAccount::<TokenAccount>::try_from(next_account_info(remaining_accounts)?)?;
+
+            require!(
+                investor_token.owner == ctx.accounts.investor.key() && investor_token.mint == mint,
+                InvestmentDaoError::InvalidGateAccount
+            );
+
+            require!(
+                investor_token.amount >= min_balance,
+                InvestmentDaoError::JoinNotAllowed
+            );
+        }
+        MembershipPolicy::CollectionGated { collection } => {
+            let investor_token =
+                InterfaceAccount::<TokenAccount>::try_from(next_account_info(remaining_accounts)?)?;
+            let raw_metadata = next_account_info(remaining_accounts)?;
+
+            require!(
+                investor_token.owner == ctx.accounts.investor.key()
+                    && raw_metadata.key() == find_metadata_account(&investor_token.mint).0,
+                InvestmentDaoError::InvalidGateAccount
+            );
+
+            //Owner of metadata account is checked on deserialization
+            let metadata = Metadata::from_account_info(raw_metadata)?;
+
+            let in_collection = matches!(
+                metadata.collection,
+                Some(nft_collection) if nft_collection.verified && nft_collection.key == collection
+            );
+
+            require!(
+                in_collection && investor_token.amount >= 1,
+                InvestmentDaoError::JoinNotAllowed
+            );
+        }
+    }
+
+    let investor_data = &mut ctx.accounts.investor_data;
+    let now = Clock::get().unwrap().unix_timestamp;
+
+    investor_data.address = ctx.accounts.investor.key();
+    investor_data.invited_at = now;
+    investor_data.joined_at = now;
+    investor_data.state = InvestorState::Accepted;
+    investor_data.allocation = None;
+    investor_data.expires_at = None;
+    investor_data.invited_by = ctx.accounts.investor.key();
+
+    investment_dao.investors_count = investment_dao.investors_count.checked_add(1).unwrap();
+
+    Ok(())
+}
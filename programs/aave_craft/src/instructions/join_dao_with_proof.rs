@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
+    merkle::{allowlist_leaf, verify_proof},
+    state::{InvestmentDao, InvestorData, InvestorState},
+};
+
+#[derive(Accounts)]
+pub struct JoinDaoWithProof<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(mut)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    #[account(init,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump,payer=investor,space=8+InvestorData::INIT_SPACE)]
+    pub investor_data: Box<Account<'info, InvestorData>>,
+    pub system_program: Program<'info, System>,
+}
+
+//Allowlisted investor joins DAO without invitation, paying for his own InvestorData
+pub fn join_dao_with_proof(
+    ctx: Context<JoinDaoWithProof>,
+    allocation: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+
+    let allowlist_root = investment_dao
+        .allowlist_root
+        .ok_or(InvestmentDaoError::AllowlistNotSet)?;
+
+    require!(
+        verify_proof(
+            &proof,
+            &allowlist_root,
+            allowlist_leaf(ctx.accounts.investor.key, allocation)
+        ),
+        InvestmentDaoError::InvalidAllowlistProof
+    );
+
+    let investor_data = &mut ctx.accounts.investor_data;
+    let now = Clock::get().unwrap().unix_timestamp;
+
+    investor_data.address = ctx.accounts.investor.key();
+    investor_data.invited_at = now;
+    investor_data.joined_at = now;
+    investor_data.state = InvestorState::Accepted;
+    investor_data.allocation = Some(allocation);
+    investor_data.expires_at = None;
+    investor_data.invited_by = ctx.accounts.investor.key();
+
+    investment_dao.investors_count = investment_dao.investors_count.checked_add(1).unwrap();
+
+    Ok(())
+}
@@ -1,15 +1,54 @@
 pub mod create_investment_dao;
 pub use create_investment_dao::*;
 
+pub mod register_dao_name;
+pub use register_dao_name::*;
+
 pub mod invite_dao_investor;
 pub use invite_dao_investor::*;
 
 pub mod accept_dao_invitation;
 pub use accept_dao_invitation::*;
 
+pub mod revoke_invitation;
+pub use revoke_invitation::*;
+
+pub mod join_dao_with_proof;
+pub use join_dao_with_proof::*;
+
+pub mod join_dao;
+pub use join_dao::*;
+
 pub mod deposit_funds;
 pub use deposit_funds::*;
 
+pub mod create_share_metadata;
+pub use create_share_metadata::*;
+
+pub mod ragequit;
+pub use ragequit::*;
+
+pub mod remove_member;
+pub use remove_member::*;
+
+pub mod transfer_ownership;
+pub use transfer_ownership::*;
+
+pub mod register_treasury_asset;
+pub use register_treasury_asset::*;
+
+pub mod sync_treasury_asset;
+pub use sync_treasury_asset::*;
+
+pub mod set_treasury_asset_price;
+pub use set_treasury_asset_price::*;
+
+pub mod propose_authority_transfer;
+pub use propose_authority_transfer::*;
+
+pub mod accept_authority_transfer;
+pub use accept_authority_transfer::*;
+
 pub mod proposal;
 pub use proposal::*;
 
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, Proposal, ProposalState},
+};
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    //proposal creator (while voting) or DAO authority (veto of succeded proposal)
+    pub authority: Signer<'info>,
+    #[account()]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut,close=proposal_authority)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    ///CHECK: checked in ix, receives rent of proposal account
+    pub proposal_authority: UncheckedAccount<'info>,
+}
+
+pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let investment_dao = &ctx.accounts.investment_dao;
+    let authority = ctx.accounts.authority.key();
+
+    require!(
+        proposal.dao == investment_dao.key(),
+        InvestmentDaoError::InvalidProposalData
+    );
+
+    require!(
+        proposal.authority == ctx.accounts.proposal_authority.key(),
+        InvestmentDaoError::InvalidProposalAuthority
+    );
+
+    match proposal.proposal_state {
+        ProposalState::Voting => {
+            //Only creator can cancel proposal that is still in voting
+            require!(
+                authority == proposal.authority,
+                InvestmentDaoError::InvalidProposalAuthority
+            );
+        }
+        ProposalState::Succeded => {
+            //Only DAO authority can veto succeded proposal, and only inside veto window
+            require!(
+                authority == investment_dao.authority,
+                InvestmentDaoError::InvalidDaoAuthority
+            );
+
+            require!(
+                Clock::get().unwrap().unix_timestamp < proposal.veto_window_ends_at(investment_dao),
+                InvestmentDaoError::VetoWindowPassed
+            );
+        }
+        _ => return Err(error!(InvestmentDaoError::InvalidProposalState)),
+    }
+
+    proposal.proposal_state = ProposalState::Canceled;
+
+    Ok(())
+}
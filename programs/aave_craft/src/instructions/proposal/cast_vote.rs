@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::{
     constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
     state::{
         InvestmentDao, InvestorData, InvestorFinancialRecord, Proposal, VoteOption, VoteRecord,
     },
@@ -14,11 +15,11 @@ pub struct CastVote<'info> {
     pub investor: UncheckedAccount<'info>,
     #[account()]
     pub investment_dao: Account<'info, InvestmentDao>,
-    #[account(seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
     pub investor_data: Account<'info, InvestorData>,
     #[account(seeds=[INVESTMENT_DAO_SEED,investor_data.key().as_ref()],bump)]
     pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
-    #[account(mut)]
+    #[account(mut,constraint=proposal.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
     pub proposal: Account<'info, Proposal>,
     #[account(init,seeds=[INVESTMENT_DAO_SEED,proposal.key().as_ref(),investor.key().as_ref()],bump,space=8+VoteRecord::INIT_SPACE,payer=investor)]
     pub vote_record: Account<'info, VoteRecord>,
@@ -31,16 +32,28 @@ pub fn cast_vote(ctx: Context<CastVote>, vote_option: VoteOption) -> Result<()>
 
     let financial_record = &ctx.accounts.investor_financial_record;
 
+    let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+    require!(
+        current_timestamp <= proposal.voting_ends_at,
+        InvestmentDaoError::VotingPeriodEnded
+    );
+
     vote_record.authority = ctx.accounts.investor.key();
-    vote_record.vote_at = Clock::get().unwrap().unix_timestamp;
+    vote_record.vote_at = current_timestamp;
     vote_record.proposal = proposal.key();
-    vote_record.voter_weight = financial_record.total_deposit_amount;
+    vote_record.dao = proposal.dao;
+    //Weight is taken from snapshot, so deposits after proposal creation don't count
+    let voter_weight = financial_record.voter_weight_at(proposal.created_slot)?;
+    vote_record.voter_weight = voter_weight;
+
+    Proposal::try_tip_vote(proposal, voter_weight, &vote_option, current_timestamp)?;
 
-    Proposal::try_tip_vote(
-        proposal,
-        financial_record.total_deposit_amount,
-        &vote_option,
-    )?;
+    if vote_option.is_supporting() {
+        let investor_data = &mut ctx.accounts.investor_data;
+        investor_data.supporting_votes_count =
+            investor_data.supporting_votes_count.saturating_add(1);
+    }
 
     vote_record.vote_option = vote_option;
     Ok(())
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, InvestorData, Proposal, VoteOption, VoteRecord},
+};
+
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    pub investor: Signer<'info>,
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,constraint=proposal.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,proposal.key().as_ref(),investor.key().as_ref()],bump,
+        constraint=vote_record.authority==investor.key() @ InvestmentDaoError::InvalidVoteRecord)]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+pub fn change_vote(ctx: Context<ChangeVote>, vote_option: VoteOption) -> Result<()> {
+    let vote_record = &mut ctx.accounts.vote_record;
+    let proposal = &mut ctx.accounts.proposal;
+
+    let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+    require!(
+        current_timestamp <= proposal.voting_ends_at,
+        InvestmentDaoError::VotingPeriodEnded
+    );
+
+    //Previous vote is removed with the same weight it was casted with
+    Proposal::remove_vote(proposal, vote_record.voter_weight, &vote_record.vote_option)?;
+
+    Proposal::try_tip_vote(
+        proposal,
+        vote_record.voter_weight,
+        &vote_option,
+        current_timestamp,
+    )?;
+
+    let investor_data = &mut ctx.accounts.investor_data;
+
+    match (
+        vote_record.vote_option.is_supporting(),
+        vote_option.is_supporting(),
+    ) {
+        (false, true) => {
+            investor_data.supporting_votes_count =
+                investor_data.supporting_votes_count.saturating_add(1)
+        }
+        (true, false) => {
+            investor_data.supporting_votes_count =
+                investor_data.supporting_votes_count.saturating_sub(1)
+        }
+        _ => {}
+    }
+
+    vote_record.vote_at = current_timestamp;
+    vote_record.vote_option = vote_option;
+    Ok(())
+}
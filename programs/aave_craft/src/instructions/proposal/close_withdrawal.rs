@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+
+use crate::{
+    constants::{INVESTMENT_DAO_TREASURY_SEED, TREASURY_ASSET_SEED, WITHDRWAL_SEED},
+    errors::InvestmentDaoError,
+    events::WithdrawalClosed,
+    state::{InvestmentDao, TreasuryAsset, WithdrawalData},
+    utils::{close_treasury_token_account, token_balance, transfer_currency},
+};
+
+#[derive(Accounts)]
+pub struct CloseWithdrawal<'info> {
+    //no checks as anyone should be able to close expired withdrawal, rent of withdrawal accounts goes to payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut,close=payer,
+        constraint=withdrawal_data.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
+    pub withdrawal_data: Account<'info, WithdrawalData>,
+    #[account(mut,seeds=[WITHDRWAL_SEED,withdrawal_data.key().as_ref()],bump)]
+    ///CHECK:seeds checked
+    pub withdrawal_treasury: UncheckedAccount<'info>,
+    #[account(mut,seeds=[INVESTMENT_DAO_TREASURY_SEED,investment_dao.key().as_ref(),withdrawal_data.currency.as_ref()],bump)]
+    ///CHECK:seeds checked
+    pub dao_treasury: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn close_withdrawal<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseWithdrawal<'info>>,
+) -> Result<()> {
+    let withdrawal_data = &ctx.accounts.withdrawal_data;
+
+    let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+    require!(
+        current_timestamp > withdrawal_data.claim_ends_at,
+        InvestmentDaoError::WithdrawalClaimPeriodNotEnded
+    );
+
+    let withdrawal_data_key = withdrawal_data.key();
+    let withdrawal_treasury_seeds: &[&[&[u8]]] = &[&[
+        WITHDRWAL_SEED,
+        withdrawal_data_key.as_ref(),
+        &[*ctx.bumps.get(&"withdrawal_treasury".to_string()).unwrap()],
+    ]];
+
+    //Unclaimed payouts (and rounding leftovers) are returned to treasury withdrawal was paid from
+    let returned_amount = if withdrawal_data.currency == Pubkey::default() {
+        let returned_amount = ctx.accounts.withdrawal_treasury.lamports();
+
+        if returned_amount > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.withdrawal_treasury.to_account_info(),
+                        to: ctx.accounts.dao_treasury.to_account_info(),
+                    },
+                    withdrawal_treasury_seeds,
+                ),
+                returned_amount,
+            )?;
+        }
+
+        returned_amount
+    } else {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let withdrawal_mint = next_account_info(remaining_accounts)?;
+
+        require!(
+            withdrawal_mint.key() == withdrawal_data.currency,
+            InvestmentDaoError::InvalidDepositMint
+        );
+
+        let withdrawal_treasury = ctx.accounts.withdrawal_treasury.to_account_info();
+        let returned_amount = token_balance(&withdrawal_treasury)?;
+
+        if returned_amount > 0 {
+            transfer_currency(
+                &withdrawal_treasury,
+                &ctx.accounts.dao_treasury.to_account_info(),
+                &withdrawal_treasury,
+                withdrawal_mint,
+                &ctx.accounts.token_program.to_account_info(),
+                returned_amount,
+                withdrawal_treasury_seeds,
+            )?;
+        }
+
+        close_treasury_token_account(
+            &withdrawal_treasury,
+            withdrawal_mint,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            withdrawal_treasury_seeds,
+        )?;
+
+        //Registered asset balance follows its treasury, same as on withdrawal execution
+        if withdrawal_data.currency != ctx.accounts.investment_dao.denominated_currency {
+            let raw_treasury_asset = next_account_info(remaining_accounts)?;
+
+            let (treasury_asset_address, _) = Pubkey::find_program_address(
+                &[
+                    TREASURY_ASSET_SEED,
+                    ctx.accounts.investment_dao.key().as_ref(),
+                    withdrawal_data.currency.as_ref(),
+                ],
+                ctx.program_id,
+            );
+
+            require!(
+                raw_treasury_asset.key() == treasury_asset_address,
+                InvestmentDaoError::InvalidTreasuryAsset
+            );
+
+            let mut treasury_asset = Account::<TreasuryAsset>::try_from(raw_treasury_asset)?;
+
+            treasury_asset.balance = token_balance(&ctx.accounts.dao_treasury.to_account_info())?;
+            treasury_asset.exit(ctx.program_id)?;
+        }
+
+        returned_amount
+    };
+
+    //Checkpoints around snapshot of closed withdrawal don't need to stay exact anymore
+    let live_withdrawals = &mut ctx.accounts.investment_dao.live_withdrawals;
+
+    if let Some(index) = live_withdrawals
+        .iter()
+        .position(|slot| *slot == withdrawal_data.snapshot_slot)
+    {
+        live_withdrawals.remove(index);
+    }
+
+    emit!(WithdrawalClosed {
+        dao: ctx.accounts.investment_dao.key(),
+        proposal: withdrawal_data.proposal,
+        returned_amount,
+        closed_at: current_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::{DAO_PROPOSAL_SEED, INVESTMENT_DAO_SEED},
-    state::{InvestmentDao, InvestorData, Proposal, ProposalState, ProposalType, VestingConfig},
+    constants::{
+        DAO_PROPOSAL_SEED, INVESTMENT_DAO_SEED, MAX_PROPOSAL_OPTIONS, MAX_TREASURY_ASSETS,
+    },
+    errors::InvestmentDaoError,
+    state::{
+        InvestmentDao, InvestorData, InvestorState, MilestoneApproval, Proposal, ProposalOption,
+        ProposalState, ProposalType, Vesting, VestingConfig,
+    },
+    utils::token_balance,
 };
 
 #[derive(Accounts)]
@@ -19,54 +26,248 @@ pub struct CreateProposal<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn create_proposal(
-    ctx: Context<CreateProposal>,
+pub fn create_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateProposal<'info>>,
     proposal_type: ProposalType,
     name: String,
     description: String,
     withdraw_amount: Option<u64>,
     vesting_config: Option<VestingConfig>,
+    investment_options: Option<Vec<VestingConfig>>,
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
 
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
     let investment_dao = &mut ctx.accounts.investment_dao;
     let investor_data = &mut ctx.accounts.investor_data;
 
+    require!(
+        investor_data.state == InvestorState::Accepted,
+        InvestmentDaoError::NotPartOfDao
+    );
+
+    //In DAO with wallet authority these actions are done by authority, proposal would override it
+    require!(
+        !proposal_type.is_authority_action()
+            || investment_dao.is_governed(&investment_dao.key(), ctx.program_id),
+        InvestmentDaoError::DaoNotGoverned
+    );
+
     investor_data.created_proposal_count =
         investor_data.created_proposal_count.checked_add(1).unwrap();
 
     investment_dao.proposals_count = investment_dao.proposals_count.checked_add(1).unwrap();
 
+    let governance_config = &investment_dao.governance_config;
+
     let voting_ends_at = Clock::get()
         .unwrap()
         .unix_timestamp
-        .checked_add(investment_dao.governance_config.max_voting_time)
+        .checked_add(governance_config.max_voting_time)
         .unwrap();
 
     proposal.voting_ends_at = voting_ends_at;
+    proposal.min_voting_ends_at = Clock::get()
+        .unwrap()
+        .unix_timestamp
+        .checked_add(governance_config.min_voting_time)
+        .unwrap();
 
-    proposal.vote_threshold = Proposal::calculate_voting_treshold(investment_dao);
+    proposal.approval_threshold = governance_config.get_approval_threshold(&proposal_type);
+    proposal.early_tipping = governance_config.early_tipping;
+    proposal.vote_threshold = Proposal::calculate_voting_treshold(investment_dao)?;
+    proposal.max_voter_weight = investment_dao.total_shares;
 
-    proposal.created_at = Clock::get().unwrap().unix_timestamp;
+    let clock = Clock::get().unwrap();
+    proposal.created_at = clock.unix_timestamp;
+    proposal.created_slot = clock.slot;
     proposal.name = name;
     proposal.description = description;
 
     proposal.proposal_type = proposal_type.clone();
     proposal.yes_votes_count = 0;
     proposal.no_votes_count = 0;
+    proposal.abstain_votes_count = 0;
+    proposal.winning_option = None;
+    proposal.succeded_at = 0;
 
     proposal.proposal_state = ProposalState::Voting;
 
+    //Funding proposals pass treasury they are paid from as first remaining account,
+    //while vesting proposals pass targeted vesting
     match proposal_type {
         ProposalType::Investing => {
-            proposal.vesting_config = vesting_config;
+            let vesting_config =
+                vesting_config.ok_or(error!(InvestmentDaoError::MissingVestingConfig))?;
+
+            vesting_config.validate()?;
+
+            let treasury_balance = get_treasury_balance(
+                next_account_info(remaining_accounts)?,
+                &investment_dao.key(),
+                investment_dao.denominated_currency,
+                ctx.program_id,
+            )?;
+
+            require!(
+                vesting_config.total_amount <= treasury_balance,
+                InvestmentDaoError::InsufficientTreasuryBalance
+            );
+
+            proposal.vesting_config = Some(vesting_config);
         }
-        ProposalType::Withdrawal => {
-            proposal.withdraw_amount = withdraw_amount;
+        ProposalType::Withdrawal { asset } => {
+            let withdraw_amount =
+                withdraw_amount.ok_or(error!(InvestmentDaoError::MissingWithdrawAmount))?;
+
+            require!(
+                withdraw_amount > 0,
+                InvestmentDaoError::InvalidWithdrawAmount
+            );
+
+            let treasury_balance = get_treasury_balance(
+                next_account_info(remaining_accounts)?,
+                &investment_dao.key(),
+                asset,
+                ctx.program_id,
+            )?;
+
+            require!(
+                withdraw_amount <= treasury_balance,
+                InvestmentDaoError::InsufficientTreasuryBalance
+            );
+
+            proposal.withdraw_amount = Some(withdraw_amount);
         }
+        ProposalType::MultiChoiceInvesting => {
+            let investment_options = investment_options.unwrap_or_default();
+
+            require!(
+                investment_options.len() >= 2 && investment_options.len() <= MAX_PROPOSAL_OPTIONS,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let treasury_balance = get_treasury_balance(
+                next_account_info(remaining_accounts)?,
+                &investment_dao.key(),
+                investment_dao.denominated_currency,
+                ctx.program_id,
+            )?;
+
+            //Any option can win, so each one has to fit treasury
+            for vesting_config in investment_options.iter() {
+                vesting_config.validate()?;
+
+                require!(
+                    vesting_config.total_amount <= treasury_balance,
+                    InvestmentDaoError::InsufficientTreasuryBalance
+                );
+            }
+
+            proposal.options = investment_options
+                .into_iter()
+                .map(|vesting_config| ProposalOption {
+                    vesting_config,
+                    votes_count: 0,
+                })
+                .collect();
+        }
+        ProposalType::UpdateGovernance { governance_config } => {
+            //Same rules as on DAO creation
+            governance_config.validate()?;
+        }
+        ProposalType::RevokeVesting { vesting } => {
+            let raw_vesting = next_account_info(remaining_accounts)?;
+
+            require!(
+                raw_vesting.key() == vesting,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let vesting_data = Account::<Vesting>::try_from(raw_vesting)?;
+
+            require!(
+                vesting_data.dao == investment_dao.key(),
+                InvestmentDaoError::InvalidProposalData
+            );
+            require!(
+                vesting_data.revoked_at.is_none(),
+                InvestmentDaoError::VestingAlreadyRevoked
+            );
+            require!(
+                vesting_data.remaining_amount > 0,
+                InvestmentDaoError::AllTokensClaimed
+            );
+        }
+        ProposalType::ApproveMilestone {
+            vesting,
+            milestone_index,
+        } => {
+            let raw_vesting = next_account_info(remaining_accounts)?;
+
+            require!(
+                raw_vesting.key() == vesting,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let vesting_data = Account::<Vesting>::try_from(raw_vesting)?;
+
+            require!(
+                vesting_data.dao == investment_dao.key(),
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            vesting_data.check_milestone_approval(milestone_index, MilestoneApproval::Authority)?;
+        }
+        ProposalType::RegisterTreasuryAsset { mint } => {
+            require!(
+                mint != investment_dao.denominated_currency,
+                InvestmentDaoError::InvalidTreasuryAsset
+            );
+            require!(
+                investment_dao.treasury_assets_count < MAX_TREASURY_ASSETS,
+                InvestmentDaoError::TreasuryAssetsLimitReached
+            );
+        }
+        ProposalType::SetTreasuryAssetPrice { mint, .. } => {
+            require!(
+                mint != investment_dao.denominated_currency,
+                InvestmentDaoError::InvalidTreasuryAsset
+            );
+        }
+        //Payload is stored inside proposal type
+        ProposalType::InviteInvestor { .. }
+        | ProposalType::TransferAuthority { .. }
+        | ProposalType::RemoveMember { .. }
+        | ProposalType::RevokeInvitation { .. }
+        | ProposalType::UpdateAllowlist { .. } => {}
     }
 
     proposal.authority = ctx.accounts.authority.key();
     proposal.dao = ctx.accounts.investment_dao.key();
     Ok(())
 }
+
+//SOL treasury holds lamports, while SPL treasury is token account (treasury of unregistered asset doesn't exist yet)
+fn get_treasury_balance(
+    dao_treasury: &AccountInfo,
+    investment_dao_address: &Pubkey,
+    treasury_currency: Pubkey,
+    program_id: &Pubkey,
+) -> Result<u64> {
+    InvestmentDao::check_treasury_seeds(
+        dao_treasury,
+        investment_dao_address,
+        treasury_currency,
+        program_id,
+    )?;
+
+    if treasury_currency == Pubkey::default() {
+        Ok(dao_treasury.lamports())
+    } else if dao_treasury.data_is_empty() {
+        Ok(0)
+    } else {
+        token_balance(dao_treasury)
+    }
+}
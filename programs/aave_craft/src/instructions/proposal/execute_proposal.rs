@@ -3,13 +3,28 @@ use anchor_lang::{
     system_program::{create_account, CreateAccount},
     Discriminator,
 };
-use anchor_spl::token::{InitializeAccount, Token, TokenAccount};
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{Token, TokenAccount},
+    token_interface::{Mint, TokenInterface},
+};
 
 use crate::{
-    constants::{INVESTMENT_DAO_TREASURY_SEED, VESTING_SEED, WITHDRWAL_SEED},
+    constants::{
+        INVESTMENT_DAO_SEED, INVESTMENT_DAO_TREASURY_SEED, MAX_LIVE_WITHDRAWALS,
+        MAX_TREASURY_ASSETS, TREASURY_ASSET_SEED, VESTING_SEED, WITHDRAWAL_CLAIM_PERIOD,
+        WITHDRWAL_SEED,
+    },
     errors::InvestmentDaoError,
+    events::{InvitationRevoked, MilestoneApproved, VestingRevoked},
     state::{
-        Currency, InvestmentDao, Proposal, ProposalState, ProposalType, Vesting, WithdrawalData,
+        Currency, InvestmentDao, InvestorData, InvestorFinancialRecord, InvestorState,
+        MilestoneApproval, Proposal, ProposalState, ProposalType, TreasuryAsset, Vesting,
+        WithdrawalData,
+    },
+    utils::{
+        close_treasury_token_account, create_treasury_token_account, pay_out_member, token_balance,
+        transfer_currency,
     },
 };
 
@@ -27,11 +42,11 @@ pub struct ExecuteProposal<'info> {
     pub investment_dao: Account<'info, InvestmentDao>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+    pub currency_token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn execute_proposal<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, ExecuteProposal<'info>>,
+pub fn execute_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
 
@@ -49,24 +64,46 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
         InvestmentDaoError::InvalidProposalState
     );
 
+    //DAO authority can veto proposal until veto window passes
+    require!(
+        Clock::get().unwrap().unix_timestamp >= proposal.veto_window_ends_at(investment_dao),
+        InvestmentDaoError::VetoWindowNotPassed
+    );
+
+    //Authority could have been transferred away from governance after proposal was created
+    require!(
+        !proposal.proposal_type.is_authority_action()
+            || investment_dao.is_governed(&investment_dao.key(), ctx.program_id),
+        InvestmentDaoError::DaoNotGoverned
+    );
+
     proposal.proposal_state = ProposalState::Executed;
 
-    let program_id = if investment_dao.currency == Currency::Sol {
-        ctx.program_id
-    } else {
-        ctx.accounts.token_program.key
+    //Withdrawals can be paid from treasury of any registered asset
+    let treasury_currency = match proposal.proposal_type {
+        ProposalType::Withdrawal { asset } => asset,
+        _ => investment_dao.denominated_currency,
     };
 
     let bump = InvestmentDao::check_treasury_seeds(
         &ctx.accounts.dao_treasury,
         &investment_dao.key(),
-        investment_dao.denominated_currency,
-        program_id,
+        treasury_currency,
+        ctx.program_id,
     )?;
 
-    match proposal.proposal_type {
-        ProposalType::Investing => {
-            let vesting_config = proposal.vesting_config.clone().unwrap();
+    let investment_dao_key = investment_dao.key();
+    let treasury_seeds: &[&[&[u8]]] = &[&[
+        INVESTMENT_DAO_TREASURY_SEED,
+        investment_dao_key.as_ref(),
+        treasury_currency.as_ref(),
+        &[bump],
+    ]];
+
+    match proposal.proposal_type.clone() {
+        ProposalType::Investing | ProposalType::MultiChoiceInvesting => {
+            //For multi-choice proposals, winning option is executed
+            let vesting_config = proposal.get_vesting_config()?;
 
             let vesting_data = next_account_info(remaining_accounts)?;
 
@@ -78,14 +115,15 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                 ctx.program_id,
             )?;
 
-            let v_data = Vesting {
-                config: vesting_config.clone(),
-                created_at: Clock::get().unwrap().unix_timestamp,
-                proposal: proposal.key(),
-                last_claim_at: 0,
-                total_claimed: 0,
-                remaining_amount: vesting_config.total_amount,
-            };
+            let (vesting_treasury_address, vt_bump) = Pubkey::find_program_address(
+                &[VESTING_SEED, vesting_data.key().as_ref()],
+                ctx.program_id,
+            );
+
+            require!(
+                vesting_treasury.key() == vesting_treasury_address,
+                InvestmentDaoError::InvalidProposalData
+            );
 
             create_account(
                 CpiContext::new_with_signer(
@@ -101,15 +139,7 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                 ctx.program_id,
             )?;
 
-            let mut data: Vec<u8> = vec![];
-            data.extend_from_slice(&Vesting::discriminator());
-            data.extend_from_slice(&v_data.try_to_vec().unwrap());
-
-            vesting_data.data.borrow_mut().copy_from_slice(&data);
-
-            msg!("CREATED VESTING DATA: {:?}", vesting_config.total_amount);
-
-            match investment_dao.currency {
+            let vested_amount = match investment_dao.currency {
                 Currency::Sol => {
                     anchor_lang::system_program::transfer(
                         CpiContext::new_with_signer(
@@ -118,63 +148,72 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                                 from: ctx.accounts.dao_treasury.to_account_info(),
                                 to: vesting_treasury.to_account_info(),
                             },
-                            &[&[
-                                INVESTMENT_DAO_TREASURY_SEED,
-                                investment_dao.key().as_ref(),
-                                investment_dao.denominated_currency.as_ref(),
-                                &[bump],
-                            ]],
+                            treasury_seeds,
                         ),
                         vesting_config.total_amount,
                     )?;
+
+                    vesting_config.total_amount
                 }
                 Currency::Spl => {
                     let vesting_mint = next_account_info(remaining_accounts)?;
 
-                    create_account(
-                        CpiContext::new(
-                            ctx.accounts.system_program.to_account_info(),
-                            CreateAccount {
-                                from: ctx.accounts.payer.to_account_info(),
-                                to: vesting_treasury.to_account_info(),
-                            },
-                        ),
-                        Rent::default().minimum_balance(TokenAccount::LEN),
-                        TokenAccount::LEN as u64,
-                        ctx.accounts.token_program.key,
+                    create_treasury_token_account(
+                        &ctx.accounts.payer.to_account_info(),
+                        vesting_treasury,
+                        vesting_mint,
+                        &ctx.accounts.currency_token_program.to_account_info(),
+                        &ctx.accounts.system_program.to_account_info(),
+                        &[&[VESTING_SEED, vesting_data.key().as_ref(), &[vt_bump]]],
                     )?;
 
-                    anchor_spl::token::initialize_account(CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        InitializeAccount {
-                            account: vesting_treasury.to_account_info(),
-                            authority: vesting_treasury.to_account_info(),
-                            mint: vesting_mint.to_account_info(),
-                            rent: ctx.accounts.rent.to_account_info(),
-                        },
-                    ))?;
-
-                    anchor_spl::token::transfer(
-                        CpiContext::new_with_signer(
-                            ctx.accounts.token_program.to_account_info(),
-                            anchor_spl::token::Transfer {
-                                authority: ctx.accounts.dao_treasury.to_account_info(),
-                                from: ctx.accounts.dao_treasury.to_account_info(),
-                                to: vesting_treasury.to_account_info(),
-                            },
-                            &[&[
-                                INVESTMENT_DAO_TREASURY_SEED,
-                                investment_dao.key().as_ref(),
-                                investment_dao.denominated_currency.as_ref(),
-                                &[bump],
-                            ]],
-                        ),
+                    transfer_currency(
+                        &ctx.accounts.dao_treasury.to_account_info(),
+                        vesting_treasury,
+                        &ctx.accounts.dao_treasury.to_account_info(),
+                        vesting_mint,
+                        &ctx.accounts.currency_token_program.to_account_info(),
                         vesting_config.total_amount,
+                        treasury_seeds,
                     )?;
+
+                    //Transfer fee (if any) is deducted, so only received amount can be claimed
+                    token_balance(vesting_treasury)?
                 }
-            }
+            };
+
+            let v_data = Vesting {
+                config: vesting_config,
+                created_at: Clock::get().unwrap().unix_timestamp,
+                proposal: proposal.key(),
+                dao: investment_dao.key(),
+                last_claim_at: 0,
+                total_claimed: 0,
+                remaining_amount: vested_amount,
+                revoked_at: None,
+            };
+
+            let mut data: Vec<u8> = vec![];
+            data.extend_from_slice(&Vesting::discriminator());
+            data.extend_from_slice(&v_data.try_to_vec().unwrap());
+
+            vesting_data.data.borrow_mut().copy_from_slice(&data);
+
+            //Invested funds still back shares, so they are kept in NAV (after transfer fee, if any)
+            ctx.accounts.investment_dao.invested_amount = ctx
+                .accounts
+                .investment_dao
+                .invested_amount
+                .checked_add(vested_amount)
+                .unwrap();
         }
-        ProposalType::Withdrawal => {
+        ProposalType::Withdrawal { asset } => {
+            //Share checkpoints are kept exact for each live withdrawal, so their number is limited
+            require!(
+                investment_dao.live_withdrawals.len() < MAX_LIVE_WITHDRAWALS,
+                InvestmentDaoError::TooManyLiveWithdrawals
+            );
+
             let withdrawal_data = next_account_info(remaining_accounts)?;
 
             let withdrawal_treasury = next_account_info(remaining_accounts)?;
@@ -185,6 +224,16 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                 ctx.program_id,
             )?;
 
+            let (withdrawal_treasury_address, wt_bump) = Pubkey::find_program_address(
+                &[WITHDRWAL_SEED, withdrawal_data.key().as_ref()],
+                ctx.program_id,
+            );
+
+            require!(
+                withdrawal_treasury.key() == withdrawal_treasury_address,
+                InvestmentDaoError::InvalidProposalData
+            );
+
             create_account(
                 CpiContext::new_with_signer(
                     ctx.accounts.system_program.to_account_info(),
@@ -199,19 +248,13 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                 ctx.program_id,
             )?;
 
-            let w_data = WithdrawalData {
-                amount: proposal.withdraw_amount.unwrap(),
-                currency: investment_dao.denominated_currency,
-                proposal: proposal.key(),
-                total_withdrawn: 0,
+            let asset_currency = if asset == Pubkey::default() {
+                Currency::Sol
+            } else {
+                Currency::Spl
             };
-            let mut data: Vec<u8> = vec![];
-            data.extend_from_slice(&WithdrawalData::discriminator());
-            data.extend_from_slice(&w_data.try_to_vec().unwrap());
 
-            withdrawal_data.data.borrow_mut().copy_from_slice(&data);
-
-            match investment_dao.currency {
+            let withdrawn_amount = match asset_currency {
                 Currency::Sol => {
                     anchor_lang::system_program::transfer(
                         CpiContext::new_with_signer(
@@ -220,61 +263,517 @@ pub fn execute_proposal<'a, 'b, 'c, 'info>(
                                 from: ctx.accounts.dao_treasury.to_account_info(),
                                 to: withdrawal_treasury.to_account_info(),
                             },
-                            &[&[
-                                INVESTMENT_DAO_TREASURY_SEED,
-                                investment_dao.key().as_ref(),
-                                investment_dao.denominated_currency.as_ref(),
-                                &[bump],
-                            ]],
+                            treasury_seeds,
                         ),
                         proposal.withdraw_amount.unwrap(),
                     )?;
+
+                    proposal.withdraw_amount.unwrap()
                 }
                 Currency::Spl => {
                     let withdrawal_mint = next_account_info(remaining_accounts)?;
 
-                    create_account(
-                        CpiContext::new(
-                            ctx.accounts.system_program.to_account_info(),
-                            CreateAccount {
-                                from: ctx.accounts.payer.to_account_info(),
-                                to: withdrawal_treasury.to_account_info(),
-                            },
-                        ),
-                        Rent::default().minimum_balance(TokenAccount::LEN),
-                        TokenAccount::LEN as u64,
-                        ctx.accounts.token_program.key,
+                    create_treasury_token_account(
+                        &ctx.accounts.payer.to_account_info(),
+                        withdrawal_treasury,
+                        withdrawal_mint,
+                        &ctx.accounts.currency_token_program.to_account_info(),
+                        &ctx.accounts.system_program.to_account_info(),
+                        &[&[WITHDRWAL_SEED, withdrawal_data.key().as_ref(), &[wt_bump]]],
                     )?;
 
-                    anchor_spl::token::initialize_account(CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        InitializeAccount {
-                            account: withdrawal_treasury.to_account_info(),
-                            authority: withdrawal_treasury.to_account_info(),
-                            mint: withdrawal_mint.to_account_info(),
-                            rent: ctx.accounts.rent.to_account_info(),
-                        },
-                    ))?;
-
-                    anchor_spl::token::transfer(
-                        CpiContext::new_with_signer(
-                            ctx.accounts.token_program.to_account_info(),
-                            anchor_spl::token::Transfer {
-                                authority: ctx.accounts.dao_treasury.to_account_info(),
-                                from: ctx.accounts.dao_treasury.to_account_info(),
-                                to: withdrawal_treasury.to_account_info(),
-                            },
-                            &[&[
-                                INVESTMENT_DAO_TREASURY_SEED,
-                                investment_dao.key().as_ref(),
-                                investment_dao.denominated_currency.as_ref(),
-                                &[bump],
-                            ]],
-                        ),
+                    transfer_currency(
+                        &ctx.accounts.dao_treasury.to_account_info(),
+                        withdrawal_treasury,
+                        &ctx.accounts.dao_treasury.to_account_info(),
+                        withdrawal_mint,
+                        &ctx.accounts.currency_token_program.to_account_info(),
                         proposal.withdraw_amount.unwrap(),
+                        treasury_seeds,
                     )?;
+
+                    if asset != investment_dao.denominated_currency {
+                        let raw_treasury_asset = next_account_info(remaining_accounts)?;
+
+                        let (treasury_asset_address, _) = Pubkey::find_program_address(
+                            &[
+                                TREASURY_ASSET_SEED,
+                                investment_dao_key.as_ref(),
+                                asset.as_ref(),
+                            ],
+                            ctx.program_id,
+                        );
+
+                        require!(
+                            raw_treasury_asset.key() == treasury_asset_address,
+                            InvestmentDaoError::InvalidTreasuryAsset
+                        );
+
+                        let mut treasury_asset =
+                            Account::<TreasuryAsset>::try_from(raw_treasury_asset)?;
+
+                        treasury_asset.balance =
+                            token_balance(&ctx.accounts.dao_treasury.to_account_info())?;
+                        treasury_asset.total_withdrawn = treasury_asset
+                            .total_withdrawn
+                            .checked_add(proposal.withdraw_amount.unwrap())
+                            .unwrap();
+
+                        treasury_asset.exit(ctx.program_id)?;
+                    }
+
+                    //Members split amount that actually arrived after transfer fee
+                    token_balance(withdrawal_treasury)?
+                }
+            };
+
+            let clock = Clock::get().unwrap();
+
+            let w_data = WithdrawalData {
+                dao: investment_dao_key,
+                amount: withdrawn_amount,
+                currency: asset,
+                proposal: proposal.key(),
+                total_withdrawn: 0,
+                total_shares: investment_dao.total_shares,
+                snapshot_slot: clock.slot,
+                claim_ends_at: clock.unix_timestamp.saturating_add(WITHDRAWAL_CLAIM_PERIOD),
+            };
+            let mut data: Vec<u8> = vec![];
+            data.extend_from_slice(&WithdrawalData::discriminator());
+            data.extend_from_slice(&w_data.try_to_vec().unwrap());
+
+            withdrawal_data.data.borrow_mut().copy_from_slice(&data);
+
+            ctx.accounts
+                .investment_dao
+                .live_withdrawals
+                .push(w_data.snapshot_slot);
+        }
+        ProposalType::UpdateGovernance { governance_config } => {
+            ctx.accounts.investment_dao.governance_config = governance_config;
+        }
+        ProposalType::InviteInvestor {
+            investor,
+            allocation,
+            expires_at,
+        } => {
+            let investor_data = next_account_info(remaining_accounts)?;
+
+            let (address, i_bump) = Pubkey::find_program_address(
+                &[
+                    INVESTMENT_DAO_SEED,
+                    investment_dao.key().as_ref(),
+                    investor.as_ref(),
+                ],
+                ctx.program_id,
+            );
+
+            require!(
+                address == investor_data.key(),
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    CreateAccount {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: investor_data.to_account_info(),
+                    },
+                    &[&[
+                        INVESTMENT_DAO_SEED,
+                        investment_dao.key().as_ref(),
+                        investor.as_ref(),
+                        &[i_bump],
+                    ]],
+                ),
+                Rent::default().minimum_balance(8 + InvestorData::INIT_SPACE),
+                8 + InvestorData::INIT_SPACE as u64,
+                ctx.program_id,
+            )?;
+
+            let i_data = InvestorData {
+                address: investor,
+                state: InvestorState::Invited,
+                joined_at: 0,
+                invited_at: Clock::get().unwrap().unix_timestamp,
+                total_deposits_count: 0,
+                created_proposal_count: 0,
+                allocation,
+                expires_at,
+                invited_by: ctx.accounts.payer.key(),
+                supporting_votes_count: 0,
+            };
+
+            let mut data: Vec<u8> = vec![];
+            data.extend_from_slice(&InvestorData::discriminator());
+            data.extend_from_slice(&i_data.try_to_vec().unwrap());
+
+            investor_data.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+        }
+        ProposalType::TransferAuthority { new_authority } => {
+            //New authority still needs to accept transfer
+            ctx.accounts.investment_dao.pending_authority = Some(new_authority);
+        }
+        ProposalType::UpdateAllowlist { allowlist_root } => {
+            //Members that already joined are not affected by root rotation
+            ctx.accounts.investment_dao.allowlist_root = allowlist_root;
+        }
+        ProposalType::RevokeVesting { vesting } => {
+            let raw_vesting = next_account_info(remaining_accounts)?;
+            let vesting_treasury = next_account_info(remaining_accounts)?;
+
+            let (vesting_treasury_address, vt_bump) =
+                Pubkey::find_program_address(&[VESTING_SEED, vesting.as_ref()], ctx.program_id);
+
+            require!(
+                raw_vesting.key() == vesting && vesting_treasury.key() == vesting_treasury_address,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let mut vesting_data = Account::<Vesting>::try_from(raw_vesting)?;
+
+            require!(
+                vesting_data.dao == investment_dao.key(),
+                InvestmentDaoError::InvalidProposalData
+            );
+            require!(
+                vesting_data.revoked_at.is_none(),
+                InvestmentDaoError::VestingAlreadyRevoked
+            );
+
+            let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+            //Vested but unclaimed tokens stay in vesting treasury for authority
+            let unvested_amount = vesting_data.unvested_amount(current_timestamp);
+
+            vesting_data.remaining_amount = vesting_data
+                .remaining_amount
+                .checked_sub(unvested_amount)
+                .unwrap();
+            vesting_data.revoked_at = Some(current_timestamp);
+
+            let vesting_treasury_seeds: &[&[&[u8]]] =
+                &[&[VESTING_SEED, vesting.as_ref(), &[vt_bump]]];
+
+            match investment_dao.currency {
+                Currency::Sol => {
+                    if unvested_amount > 0 {
+                        anchor_lang::system_program::transfer(
+                            CpiContext::new_with_signer(
+                                ctx.accounts.system_program.to_account_info(),
+                                anchor_lang::system_program::Transfer {
+                                    from: vesting_treasury.to_account_info(),
+                                    to: ctx.accounts.dao_treasury.to_account_info(),
+                                },
+                                vesting_treasury_seeds,
+                            ),
+                            unvested_amount,
+                        )?;
+                    }
+                }
+                Currency::Spl => {
+                    let vesting_mint = next_account_info(remaining_accounts)?;
+
+                    if unvested_amount > 0 {
+                        transfer_currency(
+                            vesting_treasury,
+                            &ctx.accounts.dao_treasury.to_account_info(),
+                            vesting_treasury,
+                            vesting_mint,
+                            &ctx.accounts.currency_token_program.to_account_info(),
+                            unvested_amount,
+                            vesting_treasury_seeds,
+                        )?;
+                    }
+
+                    if vesting_data.remaining_amount == 0 {
+                        close_treasury_token_account(
+                            vesting_treasury,
+                            vesting_mint,
+                            &ctx.accounts.payer.to_account_info(),
+                            &ctx.accounts.currency_token_program.to_account_info(),
+                            vesting_treasury_seeds,
+                        )?;
+                    }
                 }
             }
+
+            //Same as with last claim, drained vesting is closed (nothing was vested and left unclaimed)
+            if vesting_data.remaining_amount == 0 {
+                vesting_data.close(ctx.accounts.payer.to_account_info())?;
+            } else {
+                vesting_data.exit(ctx.program_id)?;
+            }
+
+            emit!(VestingRevoked {
+                dao: investment_dao.key(),
+                vesting,
+                returned_amount: unvested_amount,
+                revoked_at: current_timestamp,
+            });
+
+            //Returned funds are back in treasury balance
+            ctx.accounts.investment_dao.invested_amount = ctx
+                .accounts
+                .investment_dao
+                .invested_amount
+                .saturating_sub(unvested_amount);
+        }
+        ProposalType::ApproveMilestone {
+            vesting,
+            milestone_index,
+        } => {
+            let raw_vesting = next_account_info(remaining_accounts)?;
+
+            require!(
+                raw_vesting.key() == vesting,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let mut vesting_data = Account::<Vesting>::try_from(raw_vesting)?;
+
+            require!(
+                vesting_data.dao == investment_dao.key(),
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let approved_at = Clock::get().unwrap().unix_timestamp;
+            vesting_data.approve_milestone(
+                milestone_index,
+                MilestoneApproval::Authority,
+                approved_at,
+            )?;
+
+            vesting_data.exit(ctx.program_id)?;
+
+            emit!(MilestoneApproved {
+                dao: investment_dao.key(),
+                vesting,
+                milestone_index,
+                approved_at,
+            });
+        }
+        ProposalType::RegisterTreasuryAsset { mint } => {
+            let asset_mint = next_account_info(remaining_accounts)?;
+            let raw_treasury_asset = next_account_info(remaining_accounts)?;
+            let asset_treasury = next_account_info(remaining_accounts)?;
+            let asset_token_program = next_account_info(remaining_accounts)?;
+
+            let (treasury_asset_address, ta_bump) = Pubkey::find_program_address(
+                &[
+                    TREASURY_ASSET_SEED,
+                    investment_dao_key.as_ref(),
+                    mint.as_ref(),
+                ],
+                ctx.program_id,
+            );
+            let at_bump = InvestmentDao::check_treasury_seeds(
+                asset_treasury,
+                &investment_dao_key,
+                mint,
+                ctx.program_id,
+            )?;
+
+            require!(
+                asset_mint.key() == mint
+                    && raw_treasury_asset.key() == treasury_asset_address
+                    && asset_token_program.key() == *asset_mint.owner,
+                InvestmentDaoError::InvalidTreasuryAsset
+            );
+            require!(
+                investment_dao.treasury_assets_count < MAX_TREASURY_ASSETS,
+                InvestmentDaoError::TreasuryAssetsLimitReached
+            );
+
+            create_treasury_token_account(
+                &ctx.accounts.payer.to_account_info(),
+                asset_treasury,
+                asset_mint,
+                asset_token_program,
+                &ctx.accounts.system_program.to_account_info(),
+                &[&[
+                    INVESTMENT_DAO_TREASURY_SEED,
+                    investment_dao_key.as_ref(),
+                    mint.as_ref(),
+                    &[at_bump],
+                ]],
+            )?;
+
+            create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    CreateAccount {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: raw_treasury_asset.to_account_info(),
+                    },
+                    &[&[
+                        TREASURY_ASSET_SEED,
+                        investment_dao_key.as_ref(),
+                        mint.as_ref(),
+                        &[ta_bump],
+                    ]],
+                ),
+                Rent::default().minimum_balance(8 + TreasuryAsset::INIT_SPACE),
+                8 + TreasuryAsset::INIT_SPACE as u64,
+                ctx.program_id,
+            )?;
+
+            let t_data = TreasuryAsset {
+                dao: investment_dao_key,
+                mint,
+                treasury: asset_treasury.key(),
+                balance: 0,
+                total_withdrawn: 0,
+                registered_at: Clock::get().unwrap().unix_timestamp,
+                decimals: InterfaceAccount::<Mint>::try_from(asset_mint)?.decimals,
+                price: 0,
+                priced_at: 0,
+            };
+
+            let mut data: Vec<u8> = vec![];
+            data.extend_from_slice(&TreasuryAsset::discriminator());
+            data.extend_from_slice(&t_data.try_to_vec().unwrap());
+
+            raw_treasury_asset.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+
+            ctx.accounts.investment_dao.treasury_assets_count += 1;
+        }
+        ProposalType::SetTreasuryAssetPrice { mint, price } => {
+            let raw_treasury_asset = next_account_info(remaining_accounts)?;
+
+            let (treasury_asset_address, _) = Pubkey::find_program_address(
+                &[
+                    TREASURY_ASSET_SEED,
+                    investment_dao_key.as_ref(),
+                    mint.as_ref(),
+                ],
+                ctx.program_id,
+            );
+
+            require!(
+                raw_treasury_asset.key() == treasury_asset_address,
+                InvestmentDaoError::InvalidTreasuryAsset
+            );
+
+            let mut treasury_asset = Account::<TreasuryAsset>::try_from(raw_treasury_asset)?;
+
+            treasury_asset.price = price;
+            treasury_asset.priced_at = Clock::get().unwrap().unix_timestamp;
+
+            treasury_asset.exit(ctx.program_id)?;
+        }
+        ProposalType::RevokeInvitation { investor } => {
+            let raw_investor_data = next_account_info(remaining_accounts)?;
+            let inviter = next_account_info(remaining_accounts)?;
+
+            let (investor_data_address, _) = Pubkey::find_program_address(
+                &[
+                    INVESTMENT_DAO_SEED,
+                    investment_dao_key.as_ref(),
+                    investor.as_ref(),
+                ],
+                ctx.program_id,
+            );
+
+            require!(
+                raw_investor_data.key() == investor_data_address,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let investor_data = Account::<InvestorData>::try_from(raw_investor_data)?;
+
+            //Same rules as revoke_invitation
+            require!(
+                investor_data.state == InvestorState::Invited,
+                InvestmentDaoError::InvitationNotPending
+            );
+            require!(
+                inviter.key() == investor_data.invited_by,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            investor_data.close(inviter.clone())?;
+
+            emit!(InvitationRevoked {
+                dao: investment_dao_key,
+                investor,
+                revoked_at: Clock::get().unwrap().unix_timestamp,
+            });
+        }
+        ProposalType::RemoveMember { investor } => {
+            let raw_investor_data = next_account_info(remaining_accounts)?;
+            let raw_financial_record = next_account_info(remaining_accounts)?;
+            let investor_wallet = next_account_info(remaining_accounts)?;
+
+            let (investor_data_address, _) = Pubkey::find_program_address(
+                &[
+                    INVESTMENT_DAO_SEED,
+                    investment_dao.key().as_ref(),
+                    investor.as_ref(),
+                ],
+                ctx.program_id,
+            );
+            let (financial_record_address, _) = Pubkey::find_program_address(
+                &[INVESTMENT_DAO_SEED, investor_data_address.as_ref()],
+                ctx.program_id,
+            );
+
+            require!(
+                raw_investor_data.key() == investor_data_address
+                    && raw_financial_record.key() == financial_record_address
+                    && investor_wallet.key() == investor,
+                InvestmentDaoError::InvalidProposalData
+            );
+
+            let mut investor_data = Account::<InvestorData>::try_from(raw_investor_data)?;
+            let financial_record =
+                Account::<InvestorFinancialRecord>::try_from(raw_financial_record)?;
+
+            require!(
+                investor_data.state == InvestorState::Accepted,
+                InvestmentDaoError::NotPartOfDao
+            );
+
+            let share_mint = next_account_info(remaining_accounts)?;
+            let share_account =
+                Account::<TokenAccount>::try_from(next_account_info(remaining_accounts)?)?;
+
+            //Shares are always minted to investor ATA, so it holds whole balance of investor
+            require!(
+                share_account.key() == get_associated_token_address(&investor, &share_mint.key()),
+                InvestmentDaoError::InvalidShareMint
+            );
+
+            let (receiver, currency_mint) = match investment_dao.currency {
+                Currency::Sol => (investor_wallet.clone(), None),
+                Currency::Spl => (
+                    next_account_info(remaining_accounts)?.clone(),
+                    Some(next_account_info(remaining_accounts)?),
+                ),
+            };
+
+            pay_out_member(
+                &mut ctx.accounts.investment_dao,
+                &investor,
+                remaining_accounts.as_slice(),
+                &ctx.accounts.dao_treasury.to_account_info(),
+                bump,
+                share_mint,
+                &share_account,
+                financial_record.shares,
+                financial_record.total_deposit_amount,
+                &receiver,
+                currency_mint,
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.currency_token_program.to_account_info(),
+            )?;
+
+            investor_data.state = InvestorState::Removed;
+            investor_data.exit(ctx.program_id)?;
+            financial_record.close(investor_wallet.clone())?;
         }
     }
 
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Proposal;
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    //no checks as anyone should be able to finalize expired proposal
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+    Proposal::finalize(&mut ctx.accounts.proposal, current_timestamp)
+}
@@ -4,8 +4,23 @@ pub use create_proposal::*;
 pub mod cast_vote;
 pub use cast_vote::*;
 
+pub mod change_vote;
+pub use change_vote::*;
+
+pub mod relinquish_vote;
+pub use relinquish_vote::*;
+
 pub mod execute_proposal;
 pub use execute_proposal::*;
 
 pub mod withdraw_funds;
 pub use withdraw_funds::*;
+
+pub mod finalize_proposal;
+pub use finalize_proposal::*;
+
+pub mod cancel_proposal;
+pub use cancel_proposal::*;
+
+pub mod close_withdrawal;
+pub use close_withdrawal::*;
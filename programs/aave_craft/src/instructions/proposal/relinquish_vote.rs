@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, InvestorData, Proposal, ProposalState, VoteRecord},
+};
+
+#[derive(Accounts)]
+pub struct RelinquishVote<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    pub investment_dao: Account<'info, InvestmentDao>,
+    //Needed only for yes/choice votes, member that already left has none of them
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    pub investor_data: Option<Account<'info, InvestorData>>,
+    #[account(mut)]
+    ///CHECK: deserialized in ix, can be already closed if proposal was canceled
+    pub proposal: UncheckedAccount<'info>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,proposal.key().as_ref(),investor.key().as_ref()],bump,close=investor,
+        constraint=vote_record.authority==investor.key() @ InvestmentDaoError::InvalidVoteRecord,
+        constraint=vote_record.dao==investment_dao.key() @ InvestmentDaoError::InvalidVoteRecord)]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+pub fn relinquish_vote(ctx: Context<RelinquishVote>) -> Result<()> {
+    let vote_record = &ctx.accounts.vote_record;
+
+    if vote_record.vote_option.is_supporting() {
+        let investor_data = ctx
+            .accounts
+            .investor_data
+            .as_mut()
+            .ok_or(InvestmentDaoError::NotPartOfDao)?;
+
+        investor_data.supporting_votes_count =
+            investor_data.supporting_votes_count.saturating_sub(1);
+    }
+
+    //Canceled proposals are closed, so vote record can be closed right away
+    if ctx.accounts.proposal.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut proposal = Account::<Proposal>::try_from(&ctx.accounts.proposal.to_account_info())?;
+
+    require!(
+        proposal.dao == ctx.accounts.investment_dao.key(),
+        InvestmentDaoError::InvalidProposalData
+    );
+
+    //Supporting vote is locked until proposal is executed, so member can't ragequit before it
+    require!(
+        proposal.proposal_state != ProposalState::Succeded
+            || !vote_record.vote_option.is_supporting(),
+        InvestmentDaoError::SupportingVoteLocked
+    );
+
+    if proposal.proposal_state == ProposalState::Voting {
+        let current_timestamp = Clock::get().unwrap().unix_timestamp;
+
+        //Expired proposal needs to be finalized first, its votes are final
+        require!(
+            current_timestamp <= proposal.voting_ends_at,
+            InvestmentDaoError::VotingPeriodEnded
+        );
+
+        Proposal::remove_vote(
+            &mut proposal,
+            vote_record.voter_weight,
+            &vote_record.vote_option,
+        )?;
+
+        proposal.exit(ctx.program_id)?;
+    }
+
+    //For finalized proposals vote stays counted, only rent of vote record is returned
+    Ok(())
+}
@@ -1,12 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::TokenInterface;
 
 use crate::{
     constants::{INVESTMENT_DAO_SEED, WITHDRWAL_SEED},
+    errors::InvestmentDaoError,
     state::{
         Currency, InvestmentDao, InvestorData, InvestorFinancialRecord, Proposal, WithdrawalData,
         WithdrawalRecord,
     },
+    utils::transfer_currency,
 };
 
 #[derive(Accounts)]
@@ -19,9 +21,10 @@ pub struct WithdrawFunds<'info> {
     pub investor_data: Account<'info, InvestorData>,
     #[account(mut,seeds=[INVESTMENT_DAO_SEED,investor_data.key().as_ref()],bump)]
     pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
-    #[account(mut)]
+    #[account(mut,constraint=proposal.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
     pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
+    #[account(mut,seeds=[WITHDRWAL_SEED,proposal.key().as_ref()],bump,has_one=proposal @ InvestmentDaoError::InvalidProposalData,
+        constraint=withdrawal_data.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
     pub withdrawal_data: Account<'info, WithdrawalData>,
     #[account(init,seeds=[WITHDRWAL_SEED,withdrawal_data.key().as_ref(),authority.key().as_ref()],bump
     ,payer=authority,space=8+WithdrawalRecord::INIT_SPACE)]
@@ -30,32 +33,47 @@ pub struct WithdrawFunds<'info> {
     ///CHECK:seeds checked
     pub withdrawal_treasury: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn withdraw_funds<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, WithdrawFunds<'info>>,
-) -> Result<()> {
+pub fn withdraw_funds<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawFunds<'info>>) -> Result<()> {
     let withdrawal_record = &mut ctx.accounts.withdrawal_record;
 
     let withdrawal_data = &mut ctx.accounts.withdrawal_data;
     let financial_record = &mut ctx.accounts.investor_financial_record;
 
-    let withdrawable_amount = withdrawal_data.calculate_withdrawal_amount(
-        financial_record.total_deposit_amount,
-        ctx.accounts.investment_dao.total_deposited,
+    require!(
+        Clock::get().unwrap().unix_timestamp <= withdrawal_data.claim_ends_at,
+        InvestmentDaoError::WithdrawalClaimPeriodEnded
     );
 
+    let withdrawable_amount = withdrawal_data.calculate_withdrawal_amount(
+        financial_record.voter_weight_at(withdrawal_data.snapshot_slot)?,
+        withdrawal_data.total_shares,
+    )?;
+
     withdrawal_record.amount_withdrawn = withdrawable_amount;
     withdrawal_record.proposal = ctx.accounts.proposal.key();
     withdrawal_record.withdraw_at = Clock::get().unwrap().unix_timestamp;
 
+    withdrawal_data.total_withdrawn = withdrawal_data
+        .total_withdrawn
+        .checked_add(withdrawable_amount)
+        .unwrap();
+
     financial_record.total_withdrawn_amount = financial_record
         .total_withdrawn_amount
         .checked_add(withdrawable_amount)
         .unwrap();
 
-    match ctx.accounts.investment_dao.currency {
+    //Withdrawal can be paid in SOL or any token asset of DAO
+    let withdrawal_currency = if ctx.accounts.withdrawal_data.currency == Pubkey::default() {
+        Currency::Sol
+    } else {
+        Currency::Spl
+    };
+
+    match withdrawal_currency {
         Currency::Sol => {
             anchor_lang::system_program::transfer(
                 CpiContext::new_with_signer(
@@ -77,23 +95,20 @@ pub fn withdraw_funds<'a, 'b, 'c, 'info>(
             let remaining_accounts = &mut ctx.remaining_accounts.iter();
             let raw_payer_token = next_account_info(remaining_accounts)?;
 
-            let payer_token = Account::<TokenAccount>::try_from(raw_payer_token)?;
+            let withdrawal_mint = next_account_info(remaining_accounts)?;
 
-            anchor_spl::token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    anchor_spl::token::Transfer {
-                        authority: ctx.accounts.withdrawal_treasury.to_account_info(),
-                        from: ctx.accounts.withdrawal_treasury.to_account_info(),
-                        to: payer_token.to_account_info(),
-                    },
-                    &[&[
-                        WITHDRWAL_SEED,
-                        ctx.accounts.withdrawal_data.key().as_ref(),
-                        &[*ctx.bumps.get(&"withdrawal_treasury".to_string()).unwrap()],
-                    ]],
-                ),
+            transfer_currency(
+                &ctx.accounts.withdrawal_treasury.to_account_info(),
+                raw_payer_token,
+                &ctx.accounts.withdrawal_treasury.to_account_info(),
+                withdrawal_mint,
+                &ctx.accounts.token_program.to_account_info(),
                 withdrawable_amount,
+                &[&[
+                    WITHDRWAL_SEED,
+                    ctx.accounts.withdrawal_data.key().as_ref(),
+                    &[*ctx.bumps.get(&"withdrawal_treasury".to_string()).unwrap()],
+                ]],
             )?;
         }
     }
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::InvestmentDaoError, state::InvestmentDao};
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut,has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+}
+
+pub fn propose_authority_transfer(
+    ctx: Context<ProposeAuthorityTransfer>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+
+    //Transfer is completed only once new authority accepts it
+    investment_dao.pending_authority = Some(new_authority);
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::TokenInterface,
+};
+
+use crate::{
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_SEED},
+    errors::InvestmentDaoError,
+    events::MemberRagequit,
+    state::{Currency, InvestmentDao, InvestorData, InvestorFinancialRecord, InvestorState},
+    utils::pay_out_member,
+};
+
+#[derive(Accounts)]
+pub struct Ragequit<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(mut)]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut)]
+    ///CHECK: seeds checked in ix
+    pub dao_treasury: UncheckedAccount<'info>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump,close=investor)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investor_data.key().as_ref()],bump,close=investor)]
+    pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
+    #[account(mut,seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    pub share_mint: Box<Account<'info, Mint>>,
+    #[account(mut,associated_token::mint=share_mint,associated_token::authority=investor)]
+    pub investor_share_account: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub currency_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn ragequit<'info>(ctx: Context<'_, '_, '_, 'info, Ragequit<'info>>) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+
+    require!(
+        ctx.accounts.investor_data.state == InvestorState::Accepted,
+        InvestmentDaoError::NotPartOfDao
+    );
+
+    //Member can't walk away with payout while his vote still counts towards unexecuted proposal
+    require!(
+        ctx.accounts.investor_data.supporting_votes_count == 0,
+        InvestmentDaoError::MemberHasSupportingVotes
+    );
+
+    let bump = InvestmentDao::check_treasury_seeds(
+        &ctx.accounts.dao_treasury,
+        &investment_dao.key(),
+        investment_dao.denominated_currency,
+        ctx.program_id,
+    )?;
+
+    //SOL is paid out directly to investor wallet, tokens to investor token account (followed by currency mint)
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let (receiver, currency_mint) = match investment_dao.currency {
+        Currency::Sol => (ctx.accounts.investor.to_account_info(), None),
+        Currency::Spl => (
+            next_account_info(remaining_accounts)?.to_account_info(),
+            Some(next_account_info(remaining_accounts)?),
+        ),
+    };
+
+    let payout = pay_out_member(
+        investment_dao,
+        &ctx.accounts.investor.key(),
+        //Registered treasury assets follow currency accounts
+        remaining_accounts.as_slice(),
+        &ctx.accounts.dao_treasury.to_account_info(),
+        bump,
+        &ctx.accounts.share_mint.to_account_info(),
+        &ctx.accounts.investor_share_account,
+        ctx.accounts.investor_financial_record.shares,
+        ctx.accounts.investor_financial_record.total_deposit_amount,
+        &receiver,
+        currency_mint,
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.currency_token_program.to_account_info(),
+    )?;
+
+    emit!(MemberRagequit {
+        dao: investment_dao.key(),
+        investor: ctx.accounts.investor.key(),
+        shares: ctx.accounts.investor_financial_record.shares,
+        payout,
+        exited_at: Clock::get().unwrap().unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::{prelude::*, solana_program::keccak::hash};
+
+use crate::{
+    constants::DAO_NAME_SEED,
+    errors::InvestmentDaoError,
+    state::{DaoName, InvestmentDao},
+};
+
+#[derive(Accounts)]
+pub struct RegisterDaoName<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    //Name is hashed, since it can be longer than max seed length
+    #[account(init,seeds=[DAO_NAME_SEED,hash(investment_dao.name.as_bytes()).as_ref()],bump,payer=authority,space=8+DaoName::INIT_SPACE)]
+    pub dao_name: Account<'info, DaoName>,
+    pub system_program: Program<'info, System>,
+}
+
+//Reserves DAO name, so no other DAO can register the same one
+pub fn register_dao_name(ctx: Context<RegisterDaoName>) -> Result<()> {
+    let dao_name = &mut ctx.accounts.dao_name;
+
+    dao_name.dao = ctx.accounts.investment_dao.key();
+    dao_name.name = ctx.accounts.investment_dao.name.clone();
+    dao_name.registered_at = Clock::get().unwrap().unix_timestamp;
+
+    Ok(())
+}
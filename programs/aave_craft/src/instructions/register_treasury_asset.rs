@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::{
+    constants::{INVESTMENT_DAO_TREASURY_SEED, MAX_TREASURY_ASSETS, TREASURY_ASSET_SEED},
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, TreasuryAsset},
+    utils::create_treasury_token_account,
+};
+
+#[derive(Accounts)]
+pub struct RegisterTreasuryAsset<'info> {
+    //Every registered asset needs to be passed on member exits, so only DAO authority can register them
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut,has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(init,seeds=[TREASURY_ASSET_SEED,investment_dao.key().as_ref(),mint.key().as_ref()],bump,
+        payer=payer,space=8+TreasuryAsset::INIT_SPACE)]
+    pub treasury_asset: Account<'info, TreasuryAsset>,
+    #[account(mut,seeds=[INVESTMENT_DAO_TREASURY_SEED,investment_dao.key().as_ref(),mint.key().as_ref()],bump)]
+    ///CHECK: created in ix
+    pub asset_treasury: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_treasury_asset(ctx: Context<RegisterTreasuryAsset>) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+
+    //Denominated currency already has its treasury
+    require!(
+        ctx.accounts.mint.key() != investment_dao.denominated_currency,
+        InvestmentDaoError::InvalidTreasuryAsset
+    );
+    require!(
+        investment_dao.treasury_assets_count < MAX_TREASURY_ASSETS,
+        InvestmentDaoError::TreasuryAssetsLimitReached
+    );
+
+    investment_dao.treasury_assets_count += 1;
+
+    create_treasury_token_account(
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.asset_treasury.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &[&[
+            INVESTMENT_DAO_TREASURY_SEED,
+            investment_dao.key().as_ref(),
+            ctx.accounts.mint.key().as_ref(),
+            &[*ctx.bumps.get(&"asset_treasury".to_string()).unwrap()],
+        ]],
+    )?;
+
+    let treasury_asset = &mut ctx.accounts.treasury_asset;
+
+    treasury_asset.dao = investment_dao.key();
+    treasury_asset.mint = ctx.accounts.mint.key();
+    treasury_asset.treasury = ctx.accounts.asset_treasury.key();
+    treasury_asset.balance = 0;
+    treasury_asset.total_withdrawn = 0;
+    treasury_asset.registered_at = Clock::get().unwrap().unix_timestamp;
+    treasury_asset.decimals = ctx.accounts.mint.decimals;
+    treasury_asset.price = 0;
+    treasury_asset.priced_at = 0;
+
+    Ok(())
+}
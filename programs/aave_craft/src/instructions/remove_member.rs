@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::TokenInterface,
+};
+
+use crate::{
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_SEED},
+    errors::InvestmentDaoError,
+    state::{Currency, InvestmentDao, InvestorData, InvestorFinancialRecord, InvestorState},
+    utils::pay_out_member,
+};
+
+#[derive(Accounts)]
+pub struct RemoveMember<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut)]
+    ///CHECK: seeds checked in ix
+    pub dao_treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    ///CHECK: checked in seeds of investor_data
+    pub investor: UncheckedAccount<'info>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investor_data.key().as_ref()],bump,close=investor)]
+    pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
+    #[account(mut,seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    pub share_mint: Box<Account<'info, Mint>>,
+    #[account(mut,associated_token::mint=share_mint,associated_token::authority=investor)]
+    pub investor_share_account: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub currency_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn remove_member<'info>(ctx: Context<'_, '_, '_, 'info, RemoveMember<'info>>) -> Result<()> {
+    let investment_dao = &mut ctx.accounts.investment_dao;
+
+    require!(
+        ctx.accounts.investor_data.state == InvestorState::Accepted,
+        InvestmentDaoError::NotPartOfDao
+    );
+
+    let bump = InvestmentDao::check_treasury_seeds(
+        &ctx.accounts.dao_treasury,
+        &investment_dao.key(),
+        investment_dao.denominated_currency,
+        ctx.program_id,
+    )?;
+
+    //Removed member gets same pro-rata payout as with ragequit
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let (receiver, currency_mint) = match investment_dao.currency {
+        Currency::Sol => (ctx.accounts.investor.to_account_info(), None),
+        Currency::Spl => (
+            next_account_info(remaining_accounts)?.to_account_info(),
+            Some(next_account_info(remaining_accounts)?),
+        ),
+    };
+
+    pay_out_member(
+        investment_dao,
+        &ctx.accounts.investor.key(),
+        //Registered treasury assets follow currency accounts
+        remaining_accounts.as_slice(),
+        &ctx.accounts.dao_treasury.to_account_info(),
+        bump,
+        &ctx.accounts.share_mint.to_account_info(),
+        &ctx.accounts.investor_share_account,
+        ctx.accounts.investor_financial_record.shares,
+        ctx.accounts.investor_financial_record.total_deposit_amount,
+        &receiver,
+        currency_mint,
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.currency_token_program.to_account_info(),
+    )?;
+
+    ctx.accounts.investor_data.state = InvestorState::Removed;
+
+    Ok(())
+}
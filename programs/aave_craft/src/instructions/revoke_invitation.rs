@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::INVESTMENT_DAO_SEED,
+    errors::InvestmentDaoError,
+    events::InvitationRevoked,
+    state::{InvestmentDao, InvestorData, InvestorState},
+};
+
+#[derive(Accounts)]
+pub struct RevokeInvitation<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    ///CHECK: checked in seeds of investor_data
+    pub investor: UncheckedAccount<'info>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump,close=inviter)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(mut,address=investor_data.invited_by)]
+    ///CHECK: rent receiver, checked with address constraint
+    pub inviter: UncheckedAccount<'info>,
+}
+
+pub fn revoke_invitation(ctx: Context<RevokeInvitation>) -> Result<()> {
+    //Only invitations that were not resolved by invitee can be revoked
+    require!(
+        ctx.accounts.investor_data.state == InvestorState::Invited,
+        InvestmentDaoError::InvitationNotPending
+    );
+
+    emit!(InvitationRevoked {
+        dao: ctx.accounts.investment_dao.key(),
+        investor: ctx.accounts.investor.key(),
+        revoked_at: Clock::get().unwrap().unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, TreasuryAsset},
+};
+
+#[derive(Accounts)]
+pub struct SetTreasuryAssetPrice<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut,constraint=treasury_asset.dao==investment_dao.key() @ InvestmentDaoError::InvalidTreasuryAsset)]
+    pub treasury_asset: Account<'info, TreasuryAsset>,
+}
+
+//Price is used only for NAV when pricing deposits, exits pay out assets themselves. Zero price removes asset from NAV
+pub fn set_treasury_asset_price(ctx: Context<SetTreasuryAssetPrice>, price: u64) -> Result<()> {
+    let treasury_asset = &mut ctx.accounts.treasury_asset;
+
+    treasury_asset.price = price;
+    treasury_asset.priced_at = Clock::get().unwrap().unix_timestamp;
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::TreasuryAsset;
+
+#[derive(Accounts)]
+pub struct SyncTreasuryAsset<'info> {
+    #[account(mut)]
+    pub treasury_asset: Account<'info, TreasuryAsset>,
+    #[account(address=treasury_asset.treasury)]
+    pub asset_treasury: InterfaceAccount<'info, TokenAccount>,
+}
+
+//Tokens can be sent to asset treasury directly (e.g. investment returns), so balance is synced permissionlessly
+pub fn sync_treasury_asset(ctx: Context<SyncTreasuryAsset>) -> Result<()> {
+    ctx.accounts.treasury_asset.balance = ctx.accounts.asset_treasury.amount;
+
+    Ok(())
+}
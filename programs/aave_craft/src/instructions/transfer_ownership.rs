@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_SEED},
+    errors::InvestmentDaoError,
+    math::mul_div_floor,
+    state::{InvestmentDao, InvestorData, InvestorFinancialRecord, InvestorState},
+    utils::transfer_shares,
+};
+
+#[derive(Accounts)]
+pub struct TransferOwnership<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),sender.key().as_ref()],bump)]
+    pub sender_data: Account<'info, InvestorData>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,sender_data.key().as_ref()],bump)]
+    pub sender_financial_record: Account<'info, InvestorFinancialRecord>,
+    #[account(seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),recipient.key().as_ref()],bump)]
+    pub recipient_data: Account<'info, InvestorData>,
+    #[account(init_if_needed,seeds=[INVESTMENT_DAO_SEED,recipient_data.key().as_ref()],bump,space=8+InvestorFinancialRecord::INIT_SPACE,payer=recipient)]
+    pub recipient_financial_record: Account<'info, InvestorFinancialRecord>,
+    #[account(seeds=[DAO_SHARE_MINT_SEED,investment_dao.key().as_ref()],bump)]
+    pub share_mint: Box<Account<'info, Mint>>,
+    #[account(mut,associated_token::mint=share_mint,associated_token::authority=sender)]
+    pub sender_share_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut,associated_token::mint=share_mint,associated_token::authority=recipient)]
+    pub recipient_share_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn transfer_ownership(ctx: Context<TransferOwnership>, shares: u64) -> Result<()> {
+    //Ownership can move only between accepted DAO members
+    require!(
+        ctx.accounts.sender_data.state == InvestorState::Accepted
+            && ctx.accounts.recipient_data.state == InvestorState::Accepted,
+        InvestmentDaoError::NotPartOfDao
+    );
+
+    require!(
+        ctx.accounts.sender.key() != ctx.accounts.recipient.key(),
+        InvestmentDaoError::InvalidShareTransfer
+    );
+
+    let sender_record = &mut ctx.accounts.sender_financial_record;
+
+    require!(
+        shares > 0 && shares <= sender_record.shares,
+        InvestmentDaoError::InvalidShareTransfer
+    );
+
+    //Deposit amount is moved pro-rata with shares, rounded down in favor of sender
+    let moved_deposit = mul_div_floor(
+        sender_record.total_deposit_amount,
+        shares,
+        sender_record.shares,
+    )?;
+
+    let clock = Clock::get()?;
+
+    sender_record.shares = sender_record.shares.checked_sub(shares).unwrap();
+    sender_record.total_deposit_amount = sender_record
+        .total_deposit_amount
+        .checked_sub(moved_deposit)
+        .unwrap();
+    sender_record.write_checkpoint(&clock, &ctx.accounts.investment_dao.live_withdrawals)?;
+
+    let recipient_record = &mut ctx.accounts.recipient_financial_record;
+
+    recipient_record.authority = ctx.accounts.recipient.key();
+    recipient_record.shares = recipient_record.shares.checked_add(shares).unwrap();
+    recipient_record.total_deposit_amount = recipient_record
+        .total_deposit_amount
+        .checked_add(moved_deposit)
+        .unwrap();
+    recipient_record.write_checkpoint(&clock, &ctx.accounts.investment_dao.live_withdrawals)?;
+
+    //Transferred ownership counts towards recipient allocation same as deposits
+    ctx.accounts
+        .recipient_data
+        .check_allocation(recipient_record.total_deposit_amount)?;
+
+    transfer_shares(
+        &ctx.accounts.investment_dao.key(),
+        &ctx.accounts.share_mint.to_account_info(),
+        *ctx.bumps.get(&"share_mint".to_string()).unwrap(),
+        &ctx.accounts.sender_share_account,
+        &ctx.accounts.recipient_share_account,
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        shares,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::InvestmentDaoError,
+    events::MilestoneApproved,
+    state::{InvestmentDao, MilestoneApproval, Vesting},
+};
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one=authority @ InvestmentDaoError::InvalidDaoAuthority)]
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    #[account(mut,constraint=vesting.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+//Attestation of milestones approved by DAO authority, member vote milestones go through cast_milestone_vote
+pub fn approve_milestone(ctx: Context<ApproveMilestone>, milestone_index: u8) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let approved_at = Clock::get().unwrap().unix_timestamp;
+
+    vesting.approve_milestone(milestone_index, MilestoneApproval::Authority, approved_at)?;
+
+    emit!(MilestoneApproved {
+        dao: ctx.accounts.investment_dao.key(),
+        vesting: vesting.key(),
+        milestone_index,
+        approved_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{INVESTMENT_DAO_SEED, MILESTONE_VOTE_SEED},
+    errors::InvestmentDaoError,
+    events::MilestoneApproved,
+    state::{
+        InvestmentDao, InvestorData, InvestorFinancialRecord, InvestorState, MilestoneApproval,
+        MilestoneVote, MilestoneVoteRecord, Vesting,
+    },
+};
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct CastMilestoneVote<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    pub investment_dao: Box<Account<'info, InvestmentDao>>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(seeds=[INVESTMENT_DAO_SEED,investor_data.key().as_ref()],bump)]
+    pub investor_financial_record: Account<'info, InvestorFinancialRecord>,
+    #[account(mut,constraint=vesting.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(init_if_needed,seeds=[MILESTONE_VOTE_SEED,vesting.key().as_ref(),&[milestone_index]],bump,space=8+MilestoneVote::INIT_SPACE,payer=investor)]
+    pub milestone_vote: Account<'info, MilestoneVote>,
+    #[account(init,seeds=[MILESTONE_VOTE_SEED,milestone_vote.key().as_ref(),investor.key().as_ref()],bump,space=8+MilestoneVoteRecord::INIT_SPACE,payer=investor)]
+    pub milestone_vote_record: Account<'info, MilestoneVoteRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn cast_milestone_vote(ctx: Context<CastMilestoneVote>, milestone_index: u8) -> Result<()> {
+    let investment_dao = &ctx.accounts.investment_dao;
+    let investor_data = &mut ctx.accounts.investor_data;
+    let vesting = &mut ctx.accounts.vesting;
+    let milestone_vote = &mut ctx.accounts.milestone_vote;
+
+    require!(
+        investor_data.state == InvestorState::Accepted,
+        InvestmentDaoError::NotPartOfDao
+    );
+
+    vesting.check_milestone_approval(milestone_index, MilestoneApproval::MemberVote)?;
+
+    //First approval opens vote, weights and threshold are snapshotted the same way as for proposals
+    if milestone_vote.vesting == Pubkey::default() {
+        milestone_vote.dao = investment_dao.key();
+        milestone_vote.vesting = vesting.key();
+        milestone_vote.milestone_index = milestone_index;
+        milestone_vote.created_by = ctx.accounts.investor.key();
+        milestone_vote.snapshot_slot = Clock::get().unwrap().slot;
+        milestone_vote.max_voter_weight = investment_dao.total_shares;
+        milestone_vote.approval_threshold = investment_dao.governance_config.approval_threshold;
+    }
+
+    let voter_weight = ctx
+        .accounts
+        .investor_financial_record
+        .voter_weight_at(milestone_vote.snapshot_slot)?;
+
+    require!(voter_weight > 0, InvestmentDaoError::NoVoterWeight);
+
+    let milestone_vote_record = &mut ctx.accounts.milestone_vote_record;
+    milestone_vote_record.authority = ctx.accounts.investor.key();
+    milestone_vote_record.dao = investment_dao.key();
+    milestone_vote_record.milestone_vote = milestone_vote.key();
+    milestone_vote_record.voter_weight = voter_weight;
+
+    milestone_vote.approvals_weight = milestone_vote
+        .approvals_weight
+        .checked_add(voter_weight)
+        .unwrap();
+
+    //Approval counts towards milestone until relinquished, same as yes vote on proposal
+    investor_data.supporting_votes_count = investor_data.supporting_votes_count.saturating_add(1);
+
+    if milestone_vote.is_approved() {
+        let approved_at = Clock::get().unwrap().unix_timestamp;
+
+        vesting.approve_milestone(milestone_index, MilestoneApproval::MemberVote, approved_at)?;
+
+        emit!(MilestoneApproved {
+            dao: investment_dao.key(),
+            vesting: vesting.key(),
+            milestone_index,
+            approved_at,
+        });
+    }
+
+    Ok(())
+}
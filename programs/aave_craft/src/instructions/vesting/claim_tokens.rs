@@ -1,30 +1,29 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::TokenInterface;
 
 use crate::{
     constants::VESTING_SEED,
     errors::InvestmentDaoError,
     state::{Currency, InvestmentDao, Vesting},
+    utils::{close_treasury_token_account, transfer_currency},
 };
 
 #[derive(Accounts)]
 pub struct ClaimTokens<'info> {
-    #[account()]
+    #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut)]
     pub vesting: Account<'info, Vesting>,
-    #[account()]
+    #[account(constraint=vesting.dao==investment_dao.key() @ InvestmentDaoError::InvalidProposalData)]
     pub investment_dao: Account<'info, InvestmentDao>,
     #[account(mut,seeds=[VESTING_SEED,vesting.key().as_ref()],bump)]
     ///CHECK: deserialized in ix
     pub vesting_treasury: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn claim_tokens<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, ClaimTokens<'info>>,
-) -> Result<()> {
+pub fn claim_tokens<'info>(ctx: Context<'_, '_, '_, 'info, ClaimTokens<'info>>) -> Result<()> {
     let vesting = &mut ctx.accounts.vesting;
 
     let current_timestamp = Clock::get().unwrap().unix_timestamp;
@@ -34,39 +33,41 @@ pub fn claim_tokens<'a, 'b, 'c, 'info>(
         InvestmentDaoError::AllTokensClaimed
     );
 
-    let start_unlock = vesting
-        .created_at
-        .checked_add(vesting.config.cliff as i64)
-        .unwrap();
-
     require!(
         vesting.config.authority == ctx.accounts.payer.key(),
         InvestmentDaoError::InvalidVestingAuthority
     );
 
-    //dont allow claiming until cliff is over
+    //dont allow claiming until schedule starts unlocking
     require!(
-        current_timestamp > start_unlock,
+        vesting.vested_amount(current_timestamp) > 0,
         InvestmentDaoError::VestingNotStarted
     );
 
-    let time_passed_since_cliff = current_timestamp.checked_sub(start_unlock).unwrap();
+    //Only amount vested since last claim is paid out
+    let claimable_amount = vesting.claimable_amount(current_timestamp);
 
-    let passed_slots = time_passed_since_cliff
-        .checked_div(vesting.config.period)
-        .unwrap() as u64;
-
-    let remaining_accounts = &mut ctx.remaining_accounts.iter();
-    if passed_slots == 0 {
-        return Err(error!(InvestmentDaoError::NoClaimableTokens));
-    }
+    require!(claimable_amount > 0, InvestmentDaoError::NoClaimableTokens);
 
-    let claimable_amount = passed_slots
-        .checked_mul(vesting.config.amount_per_period)
+    vesting.total_claimed = vesting.total_claimed.checked_add(claimable_amount).unwrap();
+    vesting.last_claim_at = current_timestamp;
+    vesting.remaining_amount = vesting
+        .remaining_amount
+        .checked_sub(claimable_amount)
         .unwrap();
 
+    let vesting_key = vesting.key();
+    let treasury_seeds: &[&[&[u8]]] = &[&[
+        VESTING_SEED,
+        vesting_key.as_ref(),
+        &[*ctx.bumps.get(&"vesting_treasury".to_string()).unwrap()],
+    ]];
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
     match ctx.accounts.investment_dao.currency {
         Currency::Sol => {
+            //Whole SOL treasury is transferred with last claim, so account is closed as well
             anchor_lang::system_program::transfer(
                 CpiContext::new_with_signer(
                     ctx.accounts.system_program.to_account_info(),
@@ -74,11 +75,7 @@ pub fn claim_tokens<'a, 'b, 'c, 'info>(
                         from: ctx.accounts.vesting_treasury.to_account_info(),
                         to: ctx.accounts.payer.to_account_info(),
                     },
-                    &[&[
-                        VESTING_SEED,
-                        vesting.key().as_ref(),
-                        &[*ctx.bumps.get(&"vesting_treasury".to_string()).unwrap()],
-                    ]],
+                    treasury_seeds,
                 ),
                 claimable_amount,
             )?;
@@ -86,33 +83,33 @@ pub fn claim_tokens<'a, 'b, 'c, 'info>(
         Currency::Spl => {
             let raw_payer_token = next_account_info(remaining_accounts)?;
 
-            let deserialized_token = Account::<TokenAccount>::try_from(raw_payer_token)?;
+            let vesting_mint = next_account_info(remaining_accounts)?;
 
-            anchor_spl::token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    anchor_spl::token::Transfer {
-                        authority: ctx.accounts.vesting_treasury.to_account_info(),
-                        from: ctx.accounts.vesting_treasury.to_account_info(),
-                        to: deserialized_token.to_account_info(),
-                    },
-                    &[&[
-                        VESTING_SEED,
-                        vesting.key().as_ref(),
-                        &[*ctx.bumps.get(&"vesting_treasury".to_string()).unwrap()],
-                    ]],
-                ),
+            transfer_currency(
+                &ctx.accounts.vesting_treasury.to_account_info(),
+                raw_payer_token,
+                &ctx.accounts.vesting_treasury.to_account_info(),
+                vesting_mint,
+                &ctx.accounts.token_program.to_account_info(),
                 claimable_amount,
+                treasury_seeds,
             )?;
+
+            if vesting.remaining_amount == 0 {
+                close_treasury_token_account(
+                    &ctx.accounts.vesting_treasury.to_account_info(),
+                    vesting_mint,
+                    &ctx.accounts.payer.to_account_info(),
+                    &ctx.accounts.token_program.to_account_info(),
+                    treasury_seeds,
+                )?;
+            }
         }
     }
 
-    vesting.total_claimed = vesting.total_claimed.checked_add(claimable_amount).unwrap();
-    vesting.last_claim_at = Clock::get().unwrap().unix_timestamp;
-    vesting.remaining_amount = vesting
-        .remaining_amount
-        .checked_sub(claimable_amount)
-        .unwrap();
+    if vesting.remaining_amount == 0 {
+        vesting.close(ctx.accounts.payer.to_account_info())?;
+    }
 
     Ok(())
 }
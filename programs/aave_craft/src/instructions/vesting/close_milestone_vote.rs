@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::InvestmentDaoError,
+    state::{MilestoneVote, Vesting},
+};
+
+#[derive(Accounts)]
+pub struct CloseMilestoneVote<'info> {
+    //no checks as anyone should be able to close resolved vote, rent goes to wallet that opened it
+    pub payer: Signer<'info>,
+    #[account(mut,address=milestone_vote.created_by @ InvestmentDaoError::InvalidProposalData)]
+    ///CHECK: address checked
+    pub created_by: UncheckedAccount<'info>,
+    #[account(mut,close=created_by)]
+    pub milestone_vote: Account<'info, MilestoneVote>,
+    #[account(address=milestone_vote.vesting @ InvestmentDaoError::InvalidProposalData)]
+    ///CHECK: deserialized in ix, closed once vesting is fully claimed or revoked
+    pub vesting: UncheckedAccount<'info>,
+}
+
+pub fn close_milestone_vote(ctx: Context<CloseMilestoneVote>) -> Result<()> {
+    let resolved = ctx.accounts.milestone_vote.is_approved()
+        || ctx.accounts.vesting.data_is_empty()
+        || Account::<Vesting>::try_from(&ctx.accounts.vesting.to_account_info())?
+            .revoked_at
+            .is_some();
+
+    require!(resolved, InvestmentDaoError::MilestoneVoteNotResolved);
+
+    Ok(())
+}
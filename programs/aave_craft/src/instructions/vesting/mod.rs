@@ -1,2 +1,10 @@
+pub mod approve_milestone;
+pub mod cast_milestone_vote;
 pub mod claim_tokens;
+pub mod close_milestone_vote;
+pub mod relinquish_milestone_vote;
+pub use approve_milestone::*;
+pub use cast_milestone_vote::*;
 pub use claim_tokens::*;
+pub use close_milestone_vote::*;
+pub use relinquish_milestone_vote::*;
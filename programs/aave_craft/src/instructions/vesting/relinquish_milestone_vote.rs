@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{INVESTMENT_DAO_SEED, MILESTONE_VOTE_SEED},
+    errors::InvestmentDaoError,
+    state::{InvestmentDao, InvestorData, MilestoneVote, MilestoneVoteRecord},
+};
+
+#[derive(Accounts)]
+pub struct RelinquishMilestoneVote<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    pub investment_dao: Account<'info, InvestmentDao>,
+    #[account(mut,seeds=[INVESTMENT_DAO_SEED,investment_dao.key().as_ref(),investor.key().as_ref()],bump)]
+    pub investor_data: Account<'info, InvestorData>,
+    #[account(mut)]
+    ///CHECK: deserialized in ix, can be already closed once milestone is resolved
+    pub milestone_vote: UncheckedAccount<'info>,
+    #[account(mut,seeds=[MILESTONE_VOTE_SEED,milestone_vote.key().as_ref(),investor.key().as_ref()],bump,close=investor,
+        constraint=milestone_vote_record.authority==investor.key() @ InvestmentDaoError::InvalidVoteRecord,
+        constraint=milestone_vote_record.dao==investment_dao.key() @ InvestmentDaoError::InvalidVoteRecord)]
+    pub milestone_vote_record: Account<'info, MilestoneVoteRecord>,
+}
+
+pub fn relinquish_milestone_vote(ctx: Context<RelinquishMilestoneVote>) -> Result<()> {
+    let investor_data = &mut ctx.accounts.investor_data;
+
+    investor_data.supporting_votes_count = investor_data.supporting_votes_count.saturating_sub(1);
+
+    if ctx.accounts.milestone_vote.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut milestone_vote =
+        Account::<MilestoneVote>::try_from(&ctx.accounts.milestone_vote.to_account_info())?;
+
+    //Approved milestone can't be taken back, only rent of vote record is returned
+    if !milestone_vote.is_approved() {
+        milestone_vote.approvals_weight = milestone_vote
+            .approvals_weight
+            .saturating_sub(ctx.accounts.milestone_vote_record.voter_weight);
+
+        milestone_vote.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
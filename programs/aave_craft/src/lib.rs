@@ -5,6 +5,10 @@ mod instructions;
 use instructions::*;
 mod constants;
 mod errors;
+mod events;
+mod math;
+mod merkle;
+mod utils;
 
 declare_id!("BfWxKoznHmSzpGg75mUKq32nmys8tmADqSQoQjgFQRd3");
 
@@ -12,16 +16,53 @@ declare_id!("BfWxKoznHmSzpGg75mUKq32nmys8tmADqSQoQjgFQRd3");
 pub mod aave_craft {
     use super::*;
 
-    pub fn create_investment_dao<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, CreateInvestmentDao<'info>>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_investment_dao<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateInvestmentDao<'info>>,
+        nonce: u64,
         name: String,
         governance_config: Governance,
+        fundraising_config: Fundraising,
+        allowlist_root: Option<[u8; 32]>,
+        membership_policy: MembershipPolicy,
     ) -> Result<()> {
-        instructions::create_investment_dao(ctx, name, governance_config)
+        instructions::create_investment_dao(
+            ctx,
+            nonce,
+            name,
+            governance_config,
+            fundraising_config,
+            allowlist_root,
+            membership_policy,
+        )
+    }
+
+    pub fn register_dao_name(ctx: Context<RegisterDaoName>) -> Result<()> {
+        instructions::register_dao_name(ctx)
+    }
+
+    pub fn invite_dao_investor(
+        ctx: Context<InviteDaoInvestor>,
+        allocation: Option<u64>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::invite_dao_investor(ctx, allocation, expires_at)
+    }
+
+    pub fn revoke_invitation(ctx: Context<RevokeInvitation>) -> Result<()> {
+        instructions::revoke_invitation(ctx)
     }
 
-    pub fn invite_dao_investor(ctx: Context<InviteDaoInvestor>) -> Result<()> {
-        instructions::invite_dao_investor(ctx)
+    pub fn join_dao_with_proof(
+        ctx: Context<JoinDaoWithProof>,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::join_dao_with_proof(ctx, allocation, proof)
+    }
+
+    pub fn join_dao<'info>(ctx: Context<'_, '_, '_, 'info, JoinDao<'info>>) -> Result<()> {
+        instructions::join_dao(ctx)
     }
 
     pub fn accept_or_reject_dao_invitation(
@@ -31,20 +72,67 @@ pub mod aave_craft {
         instructions::accept_or_reject_dao_invitation(ctx, action)
     }
 
-    pub fn deposit_funds<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, DepositFunds<'info>>,
+    pub fn deposit_funds<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositFunds<'info>>,
         amount: u64,
     ) -> Result<()> {
         instructions::deposit_funds(ctx, amount)
     }
 
-    pub fn create_proposal(
-        ctx: Context<CreateProposal>,
+    pub fn create_share_metadata(
+        ctx: Context<CreateShareMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::create_share_metadata(ctx, name, symbol, uri)
+    }
+
+    pub fn ragequit<'info>(ctx: Context<'_, '_, '_, 'info, Ragequit<'info>>) -> Result<()> {
+        instructions::ragequit(ctx)
+    }
+
+    pub fn remove_member<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveMember<'info>>,
+    ) -> Result<()> {
+        instructions::remove_member(ctx)
+    }
+
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>, shares: u64) -> Result<()> {
+        instructions::transfer_ownership(ctx, shares)
+    }
+
+    pub fn register_treasury_asset(ctx: Context<RegisterTreasuryAsset>) -> Result<()> {
+        instructions::register_treasury_asset(ctx)
+    }
+
+    pub fn sync_treasury_asset(ctx: Context<SyncTreasuryAsset>) -> Result<()> {
+        instructions::sync_treasury_asset(ctx)
+    }
+
+    pub fn set_treasury_asset_price(ctx: Context<SetTreasuryAssetPrice>, price: u64) -> Result<()> {
+        instructions::set_treasury_asset_price(ctx, price)
+    }
+
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_authority_transfer(ctx, new_authority)
+    }
+
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        instructions::accept_authority_transfer(ctx)
+    }
+
+    pub fn create_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateProposal<'info>>,
         proposal_type: ProposalType,
         name: String,
         description: String,
         withdraw_amount: Option<u64>,
         vesting_config: Option<VestingConfig>,
+        investment_options: Option<Vec<VestingConfig>>,
     ) -> Result<()> {
         instructions::create_proposal(
             ctx,
@@ -53,6 +141,7 @@ pub mod aave_craft {
             description,
             withdraw_amount,
             vesting_config,
+            investment_options,
         )
     }
 
@@ -60,21 +149,57 @@ pub mod aave_craft {
         instructions::cast_vote(ctx, vote_option)
     }
 
-    pub fn execute_proposal<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, ExecuteProposal<'info>>,
+    pub fn change_vote(ctx: Context<ChangeVote>, vote_option: VoteOption) -> Result<()> {
+        instructions::change_vote(ctx, vote_option)
+    }
+
+    pub fn relinquish_vote(ctx: Context<RelinquishVote>) -> Result<()> {
+        instructions::relinquish_vote(ctx)
+    }
+
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        instructions::finalize_proposal(ctx)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        instructions::cancel_proposal(ctx)
+    }
+
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
     ) -> Result<()> {
         instructions::execute_proposal(ctx)
     }
 
-    pub fn claim_tokens<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, ClaimTokens<'info>>,
-    ) -> Result<()> {
+    pub fn claim_tokens<'info>(ctx: Context<'_, '_, '_, 'info, ClaimTokens<'info>>) -> Result<()> {
         instructions::claim_tokens(ctx)
     }
 
-    pub fn withdraw_funds<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, WithdrawFunds<'info>>,
+    pub fn approve_milestone(ctx: Context<ApproveMilestone>, milestone_index: u8) -> Result<()> {
+        instructions::approve_milestone(ctx, milestone_index)
+    }
+
+    pub fn cast_milestone_vote(ctx: Context<CastMilestoneVote>, milestone_index: u8) -> Result<()> {
+        instructions::cast_milestone_vote(ctx, milestone_index)
+    }
+
+    pub fn relinquish_milestone_vote(ctx: Context<RelinquishMilestoneVote>) -> Result<()> {
+        instructions::relinquish_milestone_vote(ctx)
+    }
+
+    pub fn close_milestone_vote(ctx: Context<CloseMilestoneVote>) -> Result<()> {
+        instructions::close_milestone_vote(ctx)
+    }
+
+    pub fn withdraw_funds<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFunds<'info>>,
     ) -> Result<()> {
         instructions::withdraw_funds(ctx)
     }
+
+    pub fn close_withdrawal<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseWithdrawal<'info>>,
+    ) -> Result<()> {
+        instructions::close_withdrawal(ctx)
+    }
 }
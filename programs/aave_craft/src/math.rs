@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::InvestmentDaoError;
+
+//Checked integer math used for ownership, threshold, withdrawal and vesting calculations.
+//All intermediate results are computed in u128, so `value * numerator` never overflows.
+//Each call site picks rounding explicitly: amounts paid out of a pool round down (pool can't be
+//over-distributed), while minimal requirements (quorum) round up (they can't be reached with less
+//than configured percentage).
+
+pub const PERCENTAGE_BASE: u64 = 100;
+
+//floor(value * numerator / denominator)
+pub fn mul_div_floor(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator != 0, InvestmentDaoError::MathOverflow);
+
+    let result = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(error!(InvestmentDaoError::MathOverflow))?
+        / (denominator as u128);
+
+    u64::try_from(result).map_err(|_| error!(InvestmentDaoError::MathOverflow))
+}
+
+//ceil(value * numerator / denominator)
+pub fn mul_div_ceil(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator != 0, InvestmentDaoError::MathOverflow);
+
+    let product = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(error!(InvestmentDaoError::MathOverflow))?;
+
+    let result = product
+        .checked_add(denominator as u128 - 1)
+        .ok_or(error!(InvestmentDaoError::MathOverflow))?
+        / (denominator as u128);
+
+    u64::try_from(result).map_err(|_| error!(InvestmentDaoError::MathOverflow))
+}
+
+//Exact check of part / whole >= percentage / 100, without any rounding
+pub fn reaches_percentage(part: u64, whole: u64, percentage: u8) -> bool {
+    (part as u128) * (PERCENTAGE_BASE as u128) >= (whole as u128) * (percentage as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Small deterministic generator, so tests don't need external dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, max: u64) -> u64 {
+            self.next() % max
+        }
+    }
+
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div_floor(10, 1, 3).unwrap(), 3);
+        assert_eq!(mul_div_ceil(10, 1, 3).unwrap(), 4);
+        assert_eq!(mul_div_ceil(9, 1, 3).unwrap(), 3);
+        assert_eq!(
+            mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX
+        );
+        assert!(mul_div_floor(u64::MAX, 2, 1).is_err());
+        assert!(mul_div_floor(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn no_precision_loss_on_large_amounts() {
+        //f32 can't represent 16_777_217 exactly
+        assert_eq!(mul_div_floor(16_777_217, 1, 1).unwrap(), 16_777_217);
+        assert_eq!(
+            mul_div_floor(1_000_000_000_000_000_001, 51, 100).unwrap(),
+            510_000_000_000_000_000
+        );
+    }
+
+    #[test]
+    fn reaches_percentage_is_exact() {
+        assert!(reaches_percentage(51, 100, 51));
+        assert!(!reaches_percentage(50, 100, 51));
+        assert!(reaches_percentage(0, 0, 100));
+        assert!(reaches_percentage(u64::MAX, u64::MAX, 100));
+    }
+
+    #[test]
+    fn pro_rata_payouts_never_exceed_pool() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+
+        for _ in 0..1_000 {
+            let members = 1 + rng.below(50) as usize;
+            let deposits: Vec<u64> = (0..members)
+                .map(|_| rng.below(1_000_000_000_000_000))
+                .collect();
+            let total_deposited: u64 = deposits.iter().sum();
+
+            if total_deposited == 0 {
+                continue;
+            }
+
+            let pool = rng.next();
+
+            let paid_out: u128 = deposits
+                .iter()
+                .map(|d| mul_div_floor(pool, *d, total_deposited).unwrap() as u128)
+                .sum();
+
+            //Rounding down loses at most one unit per member
+            assert!(paid_out <= pool as u128);
+            assert!(pool as u128 - paid_out < members as u128);
+        }
+    }
+
+    #[test]
+    fn sequential_exits_never_exceed_pool() {
+        let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+
+        for _ in 0..1_000 {
+            let members = 1 + rng.below(20) as usize;
+            let mut deposits: Vec<u64> = (0..members)
+                .map(|_| 1 + rng.below(1_000_000_000_000))
+                .collect();
+            let mut total_deposited: u64 = deposits.iter().sum();
+            let mut pool = rng.below(u64::MAX / 2);
+            let initial_pool = pool;
+
+            //Members leave one by one, each taking share of what is left (as in ragequit)
+            while let Some(deposit) = deposits.pop() {
+                let payout = mul_div_floor(pool, deposit, total_deposited).unwrap();
+
+                assert!(payout <= pool);
+                pool -= payout;
+                total_deposited -= deposit;
+            }
+
+            assert_eq!(total_deposited, 0);
+            assert!(pool <= initial_pool);
+        }
+    }
+
+    #[test]
+    fn quorum_rounding_up_is_never_below_percentage() {
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+
+        for _ in 0..1_000 {
+            let total = rng.next() >> 8;
+            let percentage = rng.below(101) as u8;
+
+            let quorum = mul_div_ceil(total, percentage as u64, PERCENTAGE_BASE).unwrap();
+
+            assert!(reaches_percentage(quorum, total, percentage));
+            assert!(quorum == 0 || !reaches_percentage(quorum - 1, total, percentage));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use anchor_lang::{prelude::*, solana_program::keccak::hashv};
+
+//Merkle allowlist of (wallet, allocation) pairs. Leaves and inner nodes are hashed with different
+//prefixes, so inner node can't be presented as leaf. Inner nodes hash sorted pair of children, so
+//proof doesn't need to carry left/right position.
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn allowlist_leaf(wallet: &Pubkey, allocation: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, wallet.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b]).to_bytes()
+    } else {
+        hashv(&[NODE_PREFIX, b, a]).to_bytes()
+    }
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Builds tree bottom-up (odd node is carried to next level) and returns root with proof of leaf at index
+    fn root_and_proof(leaves: &[[u8; 32]], mut index: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+        let mut level = leaves.to_vec();
+        let mut proof = vec![];
+
+        while level.len() > 1 {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            index /= 2;
+        }
+
+        (level[0], proof)
+    }
+
+    fn wallets(count: u8) -> Vec<Pubkey> {
+        (0..count)
+            .map(|i| Pubkey::new_from_array([i; 32]))
+            .collect()
+    }
+
+    #[test]
+    fn every_member_can_prove_allocation() {
+        for count in 1..=9 {
+            let wallets = wallets(count);
+            let leaves: Vec<[u8; 32]> = wallets
+                .iter()
+                .enumerate()
+                .map(|(i, wallet)| allowlist_leaf(wallet, i as u64 * 100))
+                .collect();
+
+            for (i, wallet) in wallets.iter().enumerate() {
+                let (root, proof) = root_and_proof(&leaves, i);
+
+                assert!(verify_proof(
+                    &proof,
+                    &root,
+                    allowlist_leaf(wallet, i as u64 * 100)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_allocation_wallet_or_proof() {
+        let wallets = wallets(4);
+        let leaves: Vec<[u8; 32]> = wallets
+            .iter()
+            .map(|wallet| allowlist_leaf(wallet, 100))
+            .collect();
+
+        let (root, proof) = root_and_proof(&leaves, 0);
+
+        assert!(verify_proof(&proof, &root, leaves[0]));
+        assert!(!verify_proof(
+            &proof,
+            &root,
+            allowlist_leaf(&wallets[0], 101)
+        ));
+        assert!(!verify_proof(
+            &proof,
+            &root,
+            allowlist_leaf(&Pubkey::new_unique(), 100)
+        ));
+
+        let mut tampered_proof = proof;
+        tampered_proof[0][0] ^= 1;
+        assert!(!verify_proof(&tampered_proof, &root, leaves[0]));
+    }
+}
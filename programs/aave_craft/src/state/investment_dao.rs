@@ -1,25 +1,75 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::INVESTMENT_DAO_TREASURY_SEED, errors::InvestmentDaoError};
+use crate::{
+    constants::{
+        DAO_GOVERNANCE_SEED, DAO_SHARE_MINT_SEED, INVESTMENT_DAO_TREASURY_SEED, MAX_DAO_NAME_LEN,
+        MAX_DEPOSIT_CHECKPOINTS,
+    },
+    errors::InvestmentDaoError,
+    math::mul_div_floor,
+};
+
+use super::ProposalType;
 
 #[account]
 #[derive(InitSpace)]
 pub struct InvestmentDao {
     pub authority: Pubkey,
-    #[max_len(20)]
+    //DAO address is derived from creator and nonce, so name doesn't need to be unique
+    pub creator: Pubkey,
+    pub nonce: u64,
+    #[max_len(64)]
     pub name: String,
     pub investors_count: u32,
     pub total_deposits_count: u32,
     pub total_deposited: u64,
     pub currency: Currency,
     pub governance_config: Governance,
+    pub fundraising_config: Fundraising,
+    //Merkle root of (wallet, allocation) pairs that can join DAO without invitation
+    pub allowlist_root: Option<[u8; 32]>,
+    pub membership_policy: MembershipPolicy,
     pub denominated_currency: Pubkey,
     pub proposals_count: u32,
+    //Set by current authority, new authority needs to accept transfer
+    pub pending_authority: Option<Pubkey>,
+    //Ownership of DAO is represented with shares of this mint
+    pub share_mint: Pubkey,
+    pub total_shares: u64,
+    //Funds moved to vesting treasuries by executed investments, valued at cost
+    pub invested_amount: u64,
+    //Registered treasury assets, besides denominated currency
+    pub treasury_assets_count: u8,
+    //Snapshot slots of executed withdrawals that can still be claimed, share checkpoints around them are kept exact
+    #[max_len(8)]
+    pub live_withdrawals: Vec<u64>,
 }
 
 impl InvestmentDao {
-    pub fn check_treasury_seeds<'a, 'c>(
-        dao_treasury: &'a AccountInfo<'c>,
+    //Value backing shares when pricing deposits. Funds of executed withdrawals are excluded, as they are
+    //owed only to members snapshotted at execution
+    pub fn net_asset_value(
+        &self,
+        treasury_balance: u64,
+        treasury_assets_value: u64,
+    ) -> Result<u64> {
+        treasury_balance
+            .checked_add(self.invested_amount)
+            .and_then(|value| value.checked_add(treasury_assets_value))
+            .ok_or(error!(InvestmentDaoError::MathOverflow))
+    }
+
+    //Name is stored as UTF-8 string, length is limited in bytes
+    pub fn validate_name(name: &str) -> Result<()> {
+        require!(
+            !name.trim().is_empty() && name.len() <= MAX_DAO_NAME_LEN,
+            InvestmentDaoError::InvalidDaoName
+        );
+        Ok(())
+    }
+
+    pub fn check_treasury_seeds(
+        dao_treasury: &AccountInfo,
         investment_dao_address: &Pubkey,
         denominated_currency: Pubkey,
         program_id: &Pubkey,
@@ -39,13 +89,145 @@ impl InvestmentDao {
         );
         Ok(bump)
     }
+
+    //Share mint is its own mint and freeze authority, so only program can mint, burn and move shares
+    pub fn get_share_mint_address(
+        investment_dao_address: &Pubkey,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[DAO_SHARE_MINT_SEED, investment_dao_address.as_ref()],
+            program_id,
+        )
+    }
+
+    //Authority actions of governed DAO are performed through proposals
+    pub fn is_governed(&self, investment_dao_address: &Pubkey, program_id: &Pubkey) -> bool {
+        let (governance_authority, _) =
+            InvestmentDao::get_governance_authority(investment_dao_address, program_id);
+
+        self.authority == governance_authority
+    }
+
+    //PDA that can be set as DAO authority, so authority actions are performed only through proposals
+    pub fn get_governance_authority(
+        investment_dao_address: &Pubkey,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[DAO_GOVERNANCE_SEED, investment_dao_address.as_ref()],
+            program_id,
+        )
+    }
 }
 
 #[derive(InitSpace, Clone, AnchorDeserialize, AnchorSerialize)]
 pub struct Governance {
-    //In percentages (0-100)
+    //In percentages (0-100), minimal participation out of total voter weight
     pub voting_quorum: u8,
+    //In percentages (1-100), share of yes votes (out of yes + no) needed for proposal to pass
+    pub approval_threshold: u8,
+    //Same as approval_threshold, applied to withdrawal proposals
+    pub withdrawal_approval_threshold: u8,
+    pub min_voting_time: i64,
     pub max_voting_time: i64,
+    //If enabled, proposal is resolved as soon as outcome can't change anymore
+    pub early_tipping: bool,
+    //Time (in seconds) after proposal succeeds, during which DAO authority can veto it
+    pub veto_window: i64,
+}
+
+impl Governance {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.voting_quorum <= 100
+                && self.approval_threshold > 0
+                && self.approval_threshold <= 100
+                && self.withdrawal_approval_threshold > 0
+                && self.withdrawal_approval_threshold <= 100
+                && self.min_voting_time >= 0
+                && self.max_voting_time > 0
+                && self.min_voting_time <= self.max_voting_time
+                && self.veto_window >= 0,
+            InvestmentDaoError::InvalidGovernanceConfig
+        );
+        Ok(())
+    }
+
+    pub fn get_approval_threshold(&self, proposal_type: &ProposalType) -> u8 {
+        match proposal_type {
+            ProposalType::Withdrawal { .. } => self.withdrawal_approval_threshold,
+            _ => self.approval_threshold,
+        }
+    }
+}
+
+//Parameters of DAO fundraising round, enforced on each deposit
+#[derive(InitSpace, Clone, AnchorDeserialize, AnchorSerialize)]
+pub struct Fundraising {
+    //Maximal total_deposited of DAO
+    pub hard_cap: Option<u64>,
+    //Minimal amount of single deposit
+    pub min_ticket: u64,
+    //Maximal total deposit amount of single investor
+    pub max_ticket: Option<u64>,
+    pub opens_at: Option<i64>,
+    pub closes_at: Option<i64>,
+}
+
+impl Fundraising {
+    pub fn validate(&self) -> Result<()> {
+        let valid_tickets = match self.max_ticket {
+            Some(max_ticket) => max_ticket > 0 && self.min_ticket <= max_ticket,
+            None => true,
+        };
+
+        let valid_window = match (self.opens_at, self.closes_at) {
+            (Some(opens_at), Some(closes_at)) => opens_at < closes_at,
+            _ => true,
+        };
+
+        require!(
+            valid_tickets && valid_window && self.hard_cap != Some(0),
+            InvestmentDaoError::InvalidFundraisingConfig
+        );
+        Ok(())
+    }
+
+    //Checked before funds are transferred
+    pub fn check_deposit(&self, amount: u64, now: i64) -> Result<()> {
+        if let Some(opens_at) = self.opens_at {
+            require!(now >= opens_at, InvestmentDaoError::FundraisingNotOpen);
+        }
+
+        if let Some(closes_at) = self.closes_at {
+            require!(now < closes_at, InvestmentDaoError::FundraisingClosed);
+        }
+
+        require!(
+            amount > 0 && amount >= self.min_ticket,
+            InvestmentDaoError::DepositBelowMinimum
+        );
+        Ok(())
+    }
+
+    //Checked against amounts credited after deposit
+    pub fn check_caps(&self, total_deposited: u64, investor_deposited: u64) -> Result<()> {
+        if let Some(hard_cap) = self.hard_cap {
+            require!(
+                total_deposited <= hard_cap,
+                InvestmentDaoError::HardCapExceeded
+            );
+        }
+
+        if let Some(max_ticket) = self.max_ticket {
+            require!(
+                investor_deposited <= max_ticket,
+                InvestmentDaoError::MaxTicketExceeded
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(InitSpace, Clone, PartialEq, AnchorDeserialize, AnchorSerialize)]
@@ -54,11 +236,24 @@ pub enum Currency {
     Spl,
 }
 
+//Who can become DAO member without invitation, through join_dao
+#[derive(InitSpace, Clone, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub enum MembershipPolicy {
+    InviteOnly,
+    Open,
+    //Investor needs to hold at least min_balance of given mint
+    TokenGated { mint: Pubkey, min_balance: u64 },
+    //Investor needs to hold NFT from verified Metaplex collection
+    CollectionGated { collection: Pubkey },
+}
+
 #[derive(InitSpace, Clone, PartialEq, AnchorDeserialize, AnchorSerialize)]
 pub enum InvestorState {
     Invited,
     Accepted,
     Rejected,
+    //Kept after member removal, so removed member can't join or be invited again
+    Removed,
 }
 
 #[account]
@@ -70,6 +265,30 @@ pub struct InvestorData {
     pub invited_at: i64,
     pub total_deposits_count: u32,
     pub created_proposal_count: u32,
+    //Maximal total deposit amount of investor, set on invitation
+    pub allocation: Option<u64>,
+    //Invitation can't be accepted after this timestamp
+    pub expires_at: Option<i64>,
+    //Wallet that paid for account, receives rent back if invitation is revoked
+    pub invited_by: Pubkey,
+    //Yes/choice vote records that are not relinquished yet, member can't ragequit while any is left
+    pub supporting_votes_count: u32,
+}
+
+impl InvestorData {
+    pub fn check_allocation(&self, total_deposit_amount: u64) -> Result<()> {
+        if let Some(allocation) = self.allocation {
+            require!(
+                total_deposit_amount <= allocation,
+                InvestmentDaoError::AllocationExceeded
+            );
+        }
+        Ok(())
+    }
+
+    pub fn is_invitation_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
 }
 
 #[derive(InitSpace, Clone, PartialEq, AnchorDeserialize, AnchorSerialize)]
@@ -85,4 +304,227 @@ pub struct InvestorFinancialRecord {
     pub total_deposit_amount: u64,
     pub last_deposit_at: i64,
     pub total_withdrawn_amount: u64,
+    //Mirrors share token balance of investor
+    pub shares: u64,
+    //History of share balance changes, used for calculating voter weight at proposal creation
+    #[max_len(32)]
+    pub checkpoints: Vec<DepositCheckpoint>,
+}
+
+#[derive(InitSpace, Clone, AnchorDeserialize, AnchorSerialize)]
+pub struct DepositCheckpoint {
+    pub slot: u64,
+    pub timestamp: i64,
+    pub shares: u64,
+}
+
+impl InvestorFinancialRecord {
+    //Should be called after every change of shares (deposits, exits, ownership transfers)
+    pub fn write_checkpoint(&mut self, clock: &Clock, live_withdrawals: &[u64]) -> Result<()> {
+        let checkpoint = DepositCheckpoint {
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            shares: self.shares,
+        };
+
+        match self.checkpoints.last_mut() {
+            Some(last) if last.slot == clock.slot => *last = checkpoint,
+            _ => self.checkpoints.push(checkpoint),
+        }
+
+        //Once history is full, oldest checkpoint is collapsed into previous one, which keeps lower of both
+        //balances, so voter weight in collapsed range is never overstated. Collapsing checkpoint changes weight
+        //only for slots up to next checkpoint, so checkpoints around snapshots of live withdrawals are skipped,
+        //as withdrawal payouts need exact weight
+        if self.checkpoints.len() > MAX_DEPOSIT_CHECKPOINTS {
+            let index = (1..self.checkpoints.len() - 1)
+                .find(|&i| {
+                    !live_withdrawals.iter().any(|&slot| {
+                        slot > self.checkpoints[i - 1].slot && slot <= self.checkpoints[i + 1].slot
+                    })
+                })
+                .ok_or(error!(InvestmentDaoError::CheckpointHistoryFull))?;
+
+            let collapsed = self.checkpoints.remove(index);
+            let previous = &mut self.checkpoints[index - 1];
+
+            previous.shares = previous.shares.min(collapsed.shares);
+        }
+        Ok(())
+    }
+
+    //Returns share balance as it was before given slot
+    pub fn voter_weight_at(&self, slot: u64) -> Result<u64> {
+        Ok(self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.slot < slot)
+            .map_or(0, |checkpoint| checkpoint.shares))
+    }
+}
+
+//Additional treasury token account of DAO, registered for mint other than denominated currency
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryAsset {
+    pub dao: Pubkey,
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    //Synced with treasury token account on withdrawals and sync_treasury_asset
+    pub balance: u64,
+    pub total_withdrawn: u64,
+    pub registered_at: i64,
+    pub decimals: u8,
+    //Denominated currency amount per whole asset token, set by DAO authority. Asset without price isn't part of NAV
+    pub price: u64,
+    pub priced_at: i64,
+}
+
+impl TreasuryAsset {
+    //Value of asset balance in denominated currency, rounded down in favor of existing members
+    pub fn value(&self, balance: u64) -> Result<u64> {
+        if self.price == 0 {
+            return Ok(0);
+        }
+
+        let whole_token = 10_u64
+            .checked_pow(self.decimals as u32)
+            .ok_or(error!(InvestmentDaoError::MathOverflow))?;
+
+        mul_div_floor(balance, self.price, whole_token)
+    }
+}
+
+//Optional unique vanity name of DAO, registered by DAO authority
+#[account]
+#[derive(InitSpace)]
+pub struct DaoName {
+    pub dao: Pubkey,
+    #[max_len(64)]
+    pub name: String,
+    pub registered_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAX_LIVE_WITHDRAWALS;
+
+    fn financial_record() -> InvestorFinancialRecord {
+        InvestorFinancialRecord {
+            authority: Pubkey::default(),
+            total_deposit_amount: 0,
+            last_deposit_at: 0,
+            total_withdrawn_amount: 0,
+            shares: 0,
+            checkpoints: vec![],
+        }
+    }
+
+    fn set_shares(record: &mut InvestorFinancialRecord, slot: u64, shares: u64) {
+        write_shares(record, slot, shares, &[]);
+    }
+
+    fn write_shares(
+        record: &mut InvestorFinancialRecord,
+        slot: u64,
+        shares: u64,
+        live_withdrawals: &[u64],
+    ) {
+        record.shares = shares;
+        record
+            .write_checkpoint(
+                &Clock {
+                    slot,
+                    ..Clock::default()
+                },
+                live_withdrawals,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn weight_counts_only_checkpoints_before_proposal_slot() {
+        let mut record = financial_record();
+        set_shares(&mut record, 10, 100);
+        set_shares(&mut record, 20, 300);
+        //Second change in same slot overwrites checkpoint
+        set_shares(&mut record, 20, 250);
+
+        //(proposal slot, weight)
+        let cases = [(5, 0), (10, 0), (11, 100), (20, 100), (21, 250)];
+
+        for (slot, weight) in cases {
+            assert_eq!(record.voter_weight_at(slot).unwrap(), weight);
+        }
+        assert_eq!(record.checkpoints.len(), 2);
+    }
+
+    #[test]
+    fn full_history_collapses_into_base_checkpoint() {
+        let mut record = financial_record();
+
+        //Shares grow by 10 each slot, starting at slot 1
+        for i in 1..=(MAX_DEPOSIT_CHECKPOINTS as u64 + 8) {
+            set_shares(&mut record, i, i * 10);
+        }
+
+        assert_eq!(record.checkpoints.len(), MAX_DEPOSIT_CHECKPOINTS);
+
+        //No shares before first deposit
+        assert_eq!(record.voter_weight_at(1).unwrap(), 0);
+        //Collapsed range returns base balance, which never exceeds real balance
+        assert_eq!(record.voter_weight_at(2).unwrap(), 10);
+        assert_eq!(record.voter_weight_at(9).unwrap(), 10);
+        //Range after collapsed checkpoints is exact
+        assert_eq!(record.voter_weight_at(11).unwrap(), 100);
+        assert_eq!(record.voter_weight_at(41).unwrap(), 400);
+    }
+
+    #[test]
+    fn treasury_asset_value_uses_whole_token_price() {
+        let mut treasury_asset = TreasuryAsset {
+            dao: Pubkey::default(),
+            mint: Pubkey::default(),
+            treasury: Pubkey::default(),
+            balance: 0,
+            total_withdrawn: 0,
+            registered_at: 0,
+            decimals: 6,
+            price: 0,
+            priced_at: 0,
+        };
+
+        //Unpriced asset (e.g. dust sent to treasury) doesn't change NAV
+        assert_eq!(treasury_asset.value(1_000_000).unwrap(), 0);
+
+        treasury_asset.price = 2_500;
+        assert_eq!(treasury_asset.value(3_000_000).unwrap(), 7_500);
+        assert_eq!(treasury_asset.value(399).unwrap(), 0);
+
+        treasury_asset.decimals = 20;
+        assert!(treasury_asset.value(1).is_err());
+    }
+
+    #[test]
+    fn live_withdrawal_snapshots_stay_exact() {
+        let mut record = financial_record();
+
+        //Live withdrawals packed into oldest part of history
+        let live_withdrawals: Vec<u64> = (0..MAX_LIVE_WITHDRAWALS as u64)
+            .map(|i| 3 + i * 2)
+            .collect();
+
+        for i in 1..=(MAX_DEPOSIT_CHECKPOINTS as u64 * 2) {
+            write_shares(&mut record, i, i * 10, &live_withdrawals);
+        }
+
+        assert_eq!(record.checkpoints.len(), MAX_DEPOSIT_CHECKPOINTS);
+
+        //Payout weight is balance from slot before snapshot
+        for slot in live_withdrawals {
+            assert_eq!(record.voter_weight_at(slot).unwrap(), (slot - 1) * 10);
+        }
+    }
 }
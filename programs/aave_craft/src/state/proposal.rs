@@ -1,13 +1,12 @@
-use std::ops::{Div, Mul};
-
 use anchor_lang::prelude::*;
 
 use crate::{
     constants::{VESTING_SEED, WITHDRWAL_SEED},
     errors::InvestmentDaoError,
+    math::{mul_div_ceil, mul_div_floor, reaches_percentage, PERCENTAGE_BASE},
 };
 
-use super::{InvestmentDao, VestingConfig};
+use super::{Governance, InvestmentDao, VestingConfig};
 
 #[account]
 #[derive(InitSpace)]
@@ -22,56 +21,300 @@ pub struct Proposal {
     pub withdraw_amount: Option<u64>,
     pub vesting_config: Option<VestingConfig>,
     pub proposal_state: ProposalState,
+    //Quorum, as minimal amount of votes (including abstain)
     pub vote_threshold: u64,
+    //In percentages, snapshotted from governance config for proposal type
+    pub approval_threshold: u8,
+    pub early_tipping: bool,
+    pub min_voting_ends_at: i64,
     pub voting_ends_at: i64,
     pub yes_votes_count: u64,
     pub no_votes_count: u64,
+    //Counts toward quorum, but not toward passing
+    pub abstain_votes_count: u64,
+    //Candidate investments of multi-choice proposal
+    #[max_len(5)]
+    pub options: Vec<ProposalOption>,
+    pub winning_option: Option<u8>,
     pub created_at: i64,
+    pub succeded_at: i64,
+    //Voter weights are snapshotted at this slot
+    pub created_slot: u64,
+    pub max_voter_weight: u64,
 }
 
 impl Proposal {
-    pub fn calculate_voting_treshold(investment_dao: &Account<InvestmentDao>) -> u64 {
+    pub fn calculate_voting_treshold(investment_dao: &Account<InvestmentDao>) -> Result<u64> {
         let voting_quorum = investment_dao.governance_config.voting_quorum;
-        let max_voter_weight = investment_dao.total_deposited;
-
-        let voting_threshold: f32 = (voting_quorum as f32)
-            .div(100_f32)
-            .mul(max_voter_weight as f32);
+        let max_voter_weight = investment_dao.total_shares;
 
-        voting_threshold as u64
+        //Rounded up, so quorum can't be reached with less than configured percentage
+        mul_div_ceil(max_voter_weight, voting_quorum as u64, PERCENTAGE_BASE)
     }
 
     pub fn try_tip_vote(
         proposal: &mut Account<Proposal>,
         voter_weight: u64,
         vote_option: &VoteOption,
+        current_timestamp: i64,
     ) -> Result<()> {
         if proposal.proposal_state != ProposalState::Voting {
             return Err(error!(InvestmentDaoError::ProposalNotInVotingState));
         }
 
+        proposal.check_vote_option(vote_option)?;
+
         match vote_option {
             VoteOption::No => {
                 proposal.no_votes_count =
                     proposal.no_votes_count.checked_add(voter_weight).unwrap();
-
-                //In order to proposal go to defeated,we need threshold + 1 vote
-                if proposal.no_votes_count > proposal.vote_threshold + 1 {
-                    proposal.proposal_state = ProposalState::Defeated;
-                }
             }
             VoteOption::Yes => {
                 proposal.yes_votes_count =
                     proposal.yes_votes_count.checked_add(voter_weight).unwrap();
+            }
+            VoteOption::Abstain => {
+                proposal.abstain_votes_count = proposal
+                    .abstain_votes_count
+                    .checked_add(voter_weight)
+                    .unwrap();
+            }
+            VoteOption::Choice { index } => {
+                let option = &mut proposal.options[*index as usize];
+                option.votes_count = option.votes_count.checked_add(voter_weight).unwrap();
+            }
+        }
 
-                if proposal.yes_votes_count > proposal.vote_threshold + 1 {
-                    proposal.proposal_state = ProposalState::Succeded;
-                }
+        if proposal.early_tipping && current_timestamp >= proposal.min_voting_ends_at {
+            Proposal::try_tip(proposal, current_timestamp);
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_vote(
+        proposal: &mut Proposal,
+        voter_weight: u64,
+        vote_option: &VoteOption,
+    ) -> Result<()> {
+        if proposal.proposal_state != ProposalState::Voting {
+            return Err(error!(InvestmentDaoError::ProposalNotInVotingState));
+        }
+
+        proposal.check_vote_option(vote_option)?;
+
+        match vote_option {
+            VoteOption::No => {
+                proposal.no_votes_count =
+                    proposal.no_votes_count.checked_sub(voter_weight).unwrap();
+            }
+            VoteOption::Yes => {
+                proposal.yes_votes_count =
+                    proposal.yes_votes_count.checked_sub(voter_weight).unwrap();
+            }
+            VoteOption::Abstain => {
+                proposal.abstain_votes_count = proposal
+                    .abstain_votes_count
+                    .checked_sub(voter_weight)
+                    .unwrap();
+            }
+            VoteOption::Choice { index } => {
+                let option = &mut proposal.options[*index as usize];
+                option.votes_count = option.votes_count.checked_sub(voter_weight).unwrap();
             }
         }
 
         Ok(())
     }
+
+    //Yes/No are used on single proposals, Choice only on multi-choice proposals
+    pub fn check_vote_option(&self, vote_option: &VoteOption) -> Result<()> {
+        let is_valid = match vote_option {
+            VoteOption::Yes | VoteOption::No => self.options.is_empty(),
+            VoteOption::Abstain => true,
+            VoteOption::Choice { index } => (*index as usize) < self.options.len(),
+        };
+
+        require!(is_valid, InvestmentDaoError::InvalidVoteOption);
+        Ok(())
+    }
+
+    pub fn total_votes_count(&self) -> u64 {
+        self.options.iter().fold(
+            self.yes_votes_count
+                .checked_add(self.no_votes_count)
+                .unwrap()
+                .checked_add(self.abstain_votes_count)
+                .unwrap(),
+            |total, option| total.checked_add(option.votes_count).unwrap(),
+        )
+    }
+
+    //Resolves proposal before voting ends if outcome can't change with remaining voter weight.
+    //Returns true if proposal state was changed
+    pub fn try_tip(proposal: &mut Proposal, current_timestamp: i64) -> bool {
+        let quorum_reached = proposal.total_votes_count() >= proposal.vote_threshold;
+
+        //Abstained weight can't be used for yes/no votes anymore
+        let remaining_weight = proposal
+            .max_voter_weight
+            .saturating_sub(proposal.abstain_votes_count);
+
+        if remaining_weight == 0 {
+            return false;
+        }
+
+        if proposal.options.is_empty() {
+            if quorum_reached
+                && reaches_percentage(
+                    proposal.yes_votes_count,
+                    remaining_weight,
+                    proposal.approval_threshold,
+                )
+            {
+                proposal.proposal_state = ProposalState::Succeded;
+                proposal.succeded_at = current_timestamp;
+                return true;
+            }
+
+            //Even if all remaining weight votes yes, approval can't be reached
+            let max_yes_votes = remaining_weight.saturating_sub(proposal.no_votes_count);
+            if !reaches_percentage(max_yes_votes, remaining_weight, proposal.approval_threshold) {
+                proposal.proposal_state = ProposalState::Defeated;
+                return true;
+            }
+        } else if let Some(index) = Proposal::get_leading_option(&proposal.options) {
+            let leader_votes = proposal.options[index as usize].votes_count;
+
+            let runner_up_votes = proposal
+                .options
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index as usize)
+                .map(|(_, o)| o.votes_count)
+                .max()
+                .unwrap_or(0);
+
+            let casted_votes = proposal
+                .options
+                .iter()
+                .fold(0_u64, |total, o| total.saturating_add(o.votes_count));
+            let unvoted_weight = remaining_weight.saturating_sub(casted_votes);
+
+            //Leader can't be overtaken or tied, even if all unvoted weight goes to runner-up
+            let leader_is_final = leader_votes > runner_up_votes.saturating_add(unvoted_weight);
+
+            if quorum_reached
+                && leader_is_final
+                && reaches_percentage(leader_votes, remaining_weight, proposal.approval_threshold)
+            {
+                proposal.proposal_state = ProposalState::Succeded;
+                proposal.succeded_at = current_timestamp;
+                proposal.winning_option = Some(index);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    //Called once voting time is over and proposal never tipped
+    pub fn finalize_vote(proposal: &mut Proposal, current_timestamp: i64) {
+        //Abstain votes are counted only toward quorum
+        let quorum_reached = proposal.total_votes_count() >= proposal.vote_threshold;
+
+        let succeded = if proposal.options.is_empty() {
+            let casted_votes = proposal
+                .yes_votes_count
+                .checked_add(proposal.no_votes_count)
+                .unwrap();
+
+            proposal.yes_votes_count > 0
+                && reaches_percentage(
+                    proposal.yes_votes_count,
+                    casted_votes,
+                    proposal.approval_threshold,
+                )
+        } else {
+            let casted_votes = proposal
+                .options
+                .iter()
+                .fold(0_u64, |total, o| total.checked_add(o.votes_count).unwrap());
+
+            proposal.winning_option = Proposal::get_leading_option(&proposal.options);
+
+            proposal.winning_option.map_or(false, |index| {
+                reaches_percentage(
+                    proposal.options[index as usize].votes_count,
+                    casted_votes,
+                    proposal.approval_threshold,
+                )
+            })
+        };
+
+        if quorum_reached && succeded {
+            proposal.proposal_state = ProposalState::Succeded;
+            proposal.succeded_at = current_timestamp;
+        } else {
+            proposal.proposal_state = ProposalState::Defeated;
+            proposal.winning_option = None;
+        }
+    }
+
+    //Called by finalize_proposal, resolves expired proposal, or tips it early if outcome is already decided
+    pub fn finalize(proposal: &mut Proposal, current_timestamp: i64) -> Result<()> {
+        require!(
+            proposal.proposal_state == ProposalState::Voting,
+            InvestmentDaoError::ProposalNotInVotingState
+        );
+
+        if current_timestamp > proposal.voting_ends_at {
+            Proposal::finalize_vote(proposal, current_timestamp);
+        } else {
+            //Before voting ends, proposal can be resolved only if outcome is already decided
+            require!(
+                proposal.early_tipping
+                    && current_timestamp >= proposal.min_voting_ends_at
+                    && Proposal::try_tip(proposal, current_timestamp),
+                InvestmentDaoError::VotingPeriodNotEnded
+            );
+        }
+
+        Ok(())
+    }
+
+    //Returns option with most votes, or None if there is tie for first place
+    pub fn get_leading_option(options: &[ProposalOption]) -> Option<u8> {
+        let max_votes = options.iter().map(|o| o.votes_count).max()?;
+
+        let mut leaders = options
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.votes_count == max_votes);
+
+        match (leaders.next(), leaders.next()) {
+            (Some((index, _)), None) => Some(index as u8),
+            _ => None,
+        }
+    }
+
+    //Vesting config that gets executed for investing proposals
+    pub fn get_vesting_config(&self) -> Result<VestingConfig> {
+        match self.winning_option {
+            Some(index) => Ok(self.options[index as usize].vesting_config.clone()),
+            None => self
+                .vesting_config
+                .clone()
+                .ok_or(error!(InvestmentDaoError::InvalidProposalData)),
+        }
+    }
+
+    //Saturates, as veto window is set by governance and overflow would block execution with panic
+    pub fn veto_window_ends_at(&self, investment_dao: &InvestmentDao) -> i64 {
+        self.succeded_at
+            .saturating_add(investment_dao.governance_config.veto_window)
+    }
+
     pub fn check_withdrwal_data_seeds(
         &self,
         withdrawal_data: AccountInfo,
@@ -117,6 +360,21 @@ pub enum ProposalState {
 pub enum VoteOption {
     Yes,
     No,
+    Abstain,
+    Choice { index: u8 },
+}
+
+impl VoteOption {
+    //Options that push proposal towards execution
+    pub fn is_supporting(&self) -> bool {
+        matches!(self, VoteOption::Yes | VoteOption::Choice { .. })
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, InitSpace)]
+pub struct ProposalOption {
+    pub vesting_config: VestingConfig,
+    pub votes_count: u64,
 }
 
 #[account]
@@ -125,6 +383,8 @@ pub enum VoteOption {
 pub struct VoteRecord {
     pub authority: Pubkey,
     pub proposal: Pubkey,
+    //Proposal can be already closed on relinquish, so DAO is checked against record
+    pub dao: Pubkey,
     pub vote_at: i64,
     pub vote_option: VoteOption,
     pub voter_weight: u64,
@@ -133,16 +393,81 @@ pub struct VoteRecord {
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, InitSpace)]
 pub enum ProposalType {
     Investing,
-    Withdrawal,
+    //Asset is denominated currency or mint of registered treasury asset
+    Withdrawal {
+        asset: Pubkey,
+    },
+    //Investors choose between multiple candidate investments
+    MultiChoiceInvesting,
+    UpdateGovernance {
+        governance_config: Governance,
+    },
+    //Used when DAO authority is governance PDA
+    InviteInvestor {
+        investor: Pubkey,
+        allocation: Option<u64>,
+        expires_at: Option<i64>,
+    },
+    TransferAuthority {
+        new_authority: Pubkey,
+    },
+    RemoveMember {
+        investor: Pubkey,
+    },
+    UpdateAllowlist {
+        allowlist_root: Option<[u8; 32]>,
+    },
+    //Clawback of unvested tokens from vesting of executed investment
+    RevokeVesting {
+        vesting: Pubkey,
+    },
+    //Authority approval of milestone in governed DAO, member vote milestones use cast_milestone_vote
+    ApproveMilestone {
+        vesting: Pubkey,
+        milestone_index: u8,
+    },
+    //Authority actions of governed DAO
+    RegisterTreasuryAsset {
+        mint: Pubkey,
+    },
+    RevokeInvitation {
+        investor: Pubkey,
+    },
+    SetTreasuryAssetPrice {
+        mint: Pubkey,
+        price: u64,
+    },
+}
+
+impl ProposalType {
+    //Actions that DAO authority performs directly, so only governed DAO can propose them
+    pub fn is_authority_action(&self) -> bool {
+        matches!(
+            self,
+            ProposalType::InviteInvestor { .. }
+                | ProposalType::TransferAuthority { .. }
+                | ProposalType::RemoveMember { .. }
+                | ProposalType::RegisterTreasuryAsset { .. }
+                | ProposalType::RevokeInvitation { .. }
+                | ProposalType::SetTreasuryAssetPrice { .. }
+                | ProposalType::ApproveMilestone { .. }
+        )
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct WithdrawalData {
+    pub dao: Pubkey,
     pub proposal: Pubkey,
     pub amount: u64,
     pub currency: Pubkey,
     pub total_withdrawn: u64,
+    //Ownership is snapshotted at execution, so later deposits and exits don't change shares
+    pub total_shares: u64,
+    pub snapshot_slot: u64,
+    //Unclaimed funds can be returned to DAO treasury after this timestamp
+    pub claim_ends_at: i64,
 }
 
 #[account]
@@ -154,13 +479,211 @@ pub struct WithdrawalRecord {
 }
 
 impl WithdrawalData {
-    pub fn calculate_withdrawal_amount(&self, deposit_amount: u64, total_rights: u64) -> u64 {
-        let amount = self.amount;
+    pub fn calculate_withdrawal_amount(
+        &self,
+        deposit_amount: u64,
+        total_rights: u64,
+    ) -> Result<u64> {
+        //Rounded down, so sum of all members withdrawals never exceeds withdrawal amount
+        mul_div_floor(self.amount, deposit_amount, total_rights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::VestingSchedule;
+
+    fn proposal(max_voter_weight: u64, vote_threshold: u64, approval_threshold: u8) -> Proposal {
+        Proposal {
+            dao: Pubkey::default(),
+            authority: Pubkey::default(),
+            name: String::new(),
+            description: String::new(),
+            proposal_type: ProposalType::Investing,
+            withdraw_amount: None,
+            vesting_config: None,
+            proposal_state: ProposalState::Voting,
+            vote_threshold,
+            approval_threshold,
+            early_tipping: true,
+            min_voting_ends_at: 0,
+            voting_ends_at: 1_000,
+            yes_votes_count: 0,
+            no_votes_count: 0,
+            abstain_votes_count: 0,
+            options: vec![],
+            winning_option: None,
+            created_at: 0,
+            succeded_at: 0,
+            created_slot: 0,
+            max_voter_weight,
+        }
+    }
+
+    fn multi_choice_proposal(
+        max_voter_weight: u64,
+        approval_threshold: u8,
+        votes: &[u64],
+    ) -> Proposal {
+        let mut proposal = proposal(max_voter_weight, 0, approval_threshold);
+
+        proposal.options = votes
+            .iter()
+            .map(|votes_count| ProposalOption {
+                vesting_config: VestingConfig {
+                    total_amount: 1,
+                    authority: Pubkey::default(),
+                    schedule: VestingSchedule::Linear { start: 0, end: 1 },
+                },
+                votes_count: *votes_count,
+            })
+            .collect();
+        proposal
+    }
+
+    //(vote threshold, approval threshold, yes, no, abstain, early tipping, timestamp, expected state,
+    //None if voting period hasn't ended). Voting ends at 1_000, so earlier timestamps can only tip
+    type VoteCase = (u64, u8, u64, u64, u64, bool, i64, Option<ProposalState>);
+
+    #[test]
+    fn finalize_yes_no() {
+        use ProposalState::{Defeated, Succeded};
+
+        let cases: Vec<VoteCase> = vec![
+            //Quorum not reached, even though yes votes have majority of all weight
+            (70, 50, 60, 0, 0, true, 500, None),
+            (60, 50, 60, 0, 0, true, 500, Some(Succeded)),
+            //Exact approval threshold of all remaining weight
+            (10, 60, 60, 0, 0, true, 500, Some(Succeded)),
+            (10, 60, 59, 0, 0, true, 500, None),
+            //Defeated once remaining weight can't reach threshold
+            (10, 60, 0, 41, 0, true, 500, Some(Defeated)),
+            (10, 60, 0, 40, 0, true, 500, None),
+            //Abstain votes reduce remaining weight and count toward quorum
+            (50, 50, 25, 0, 50, true, 500, Some(Succeded)),
+            (10, 50, 0, 0, 100, true, 500, None),
+            //Decided outcome tips early only if early tipping is on
+            (10, 50, 60, 0, 0, false, 500, None),
+            (10, 50, 60, 0, 0, false, 1_001, Some(Succeded)),
+            //Undecided proposal can't be finalized before deadline, including deadline itself
+            (10, 50, 30, 20, 0, true, 1_000, None),
+            //Once deadline passes, quorum and approval threshold of casted votes decide
+            (10, 50, 30, 20, 0, true, 1_001, Some(Succeded)),
+            (10, 50, 20, 30, 0, true, 1_001, Some(Defeated)),
+            (50, 50, 30, 0, 0, true, 1_001, Some(Defeated)),
+            (50, 50, 30, 0, 20, true, 1_001, Some(Succeded)),
+            //Exact approval threshold of casted votes, abstain isn't counted
+            (10, 60, 60, 40, 0, true, 1_001, Some(Succeded)),
+            (10, 60, 59, 41, 0, true, 1_001, Some(Defeated)),
+            (10, 60, 30, 20, 50, true, 1_001, Some(Succeded)),
+            (10, 67, 66, 34, 0, true, 1_001, Some(Defeated)),
+            //Only abstain votes, or no votes at all
+            (10, 50, 0, 0, 100, true, 1_001, Some(Defeated)),
+            (0, 50, 0, 0, 0, true, 1_001, Some(Defeated)),
+        ];
+
+        for (vote_threshold, approval_threshold, yes, no, abstain, early_tipping, now, expected) in
+            cases
+        {
+            let mut proposal = proposal(100, vote_threshold, approval_threshold);
+            proposal.yes_votes_count = yes;
+            proposal.no_votes_count = no;
+            proposal.abstain_votes_count = abstain;
+            proposal.early_tipping = early_tipping;
+
+            let result = Proposal::finalize(&mut proposal, now);
+
+            match expected {
+                Some(state) => {
+                    assert!(
+                        result.is_ok() && proposal.proposal_state == state,
+                        "{:?}",
+                        (vote_threshold, approval_threshold, yes, no, abstain, now)
+                    );
+                    assert_eq!(
+                        proposal.succeded_at,
+                        if state == ProposalState::Succeded {
+                            now
+                        } else {
+                            0
+                        }
+                    );
+                }
+                None => {
+                    assert_eq!(
+                        result,
+                        Err(error!(InvestmentDaoError::VotingPeriodNotEnded))
+                    );
+                    assert!(
+                        proposal.proposal_state == ProposalState::Voting,
+                        "{:?}",
+                        (vote_threshold, approval_threshold, yes, no, abstain, now)
+                    );
+                }
+            }
+        }
+
+        //Minimum voting time blocks early tipping
+        let mut proposal = proposal(100, 10, 50);
+        proposal.yes_votes_count = 60;
+        proposal.min_voting_ends_at = 600;
+        assert!(Proposal::finalize(&mut proposal, 500).is_err());
+        Proposal::finalize(&mut proposal, 600).unwrap();
+        assert_eq!(proposal.succeded_at, 600);
+
+        //Finalized proposal can't be finalized again
+        assert_eq!(
+            Proposal::finalize(&mut proposal, 2_000),
+            Err(error!(InvestmentDaoError::ProposalNotInVotingState))
+        );
+    }
 
-        let ownership = (deposit_amount as f32).div(total_rights as f32);
+    #[test]
+    fn multi_choice_tips_only_when_leader_is_final() {
+        //(max voter weight, approval threshold, option votes, tipped winner)
+        let cases: Vec<(u64, u8, Vec<u64>, Option<u8>)> = vec![
+            //40% reaches threshold, but remaining 40 can still go to second option
+            (100, 40, vec![40, 20], None),
+            //Runner-up can at most tie with leader
+            (100, 40, vec![50, 10], None),
+            (100, 40, vec![51, 10], Some(0)),
+            (100, 40, vec![10, 30, 60], Some(2)),
+            //Leader is final, but can't reach approval threshold of all weight
+            (100, 70, vec![60, 40], None),
+            (100, 50, vec![30, 30], None),
+        ];
 
-        let withdrawable = (amount as f32).mul(ownership) as u64;
+        for (max_voter_weight, approval_threshold, votes, winner) in cases {
+            let mut proposal = multi_choice_proposal(max_voter_weight, approval_threshold, &votes);
 
-        withdrawable
+            assert_eq!(Proposal::try_tip(&mut proposal, 10), winner.is_some());
+            assert_eq!(proposal.winning_option, winner);
+        }
+    }
+
+    #[test]
+    fn multi_choice_finalize_picks_unique_leader() {
+        //(approval threshold, option votes, winner)
+        let cases: Vec<(u8, Vec<u64>, Option<u8>)> = vec![
+            (50, vec![40, 20], Some(0)),
+            (50, vec![20, 20, 10], None),
+            //Leader has 40% of casted votes
+            (50, vec![40, 30, 30], None),
+            (40, vec![40, 30, 30], Some(0)),
+            (50, vec![0, 0], None),
+        ];
+
+        for (approval_threshold, votes, winner) in cases {
+            let mut proposal = multi_choice_proposal(100, approval_threshold, &votes);
+
+            Proposal::finalize_vote(&mut proposal, 2_000);
+
+            assert_eq!(proposal.winning_option, winner);
+            assert_eq!(
+                proposal.proposal_state == ProposalState::Succeded,
+                winner.is_some()
+            );
+        }
     }
 }
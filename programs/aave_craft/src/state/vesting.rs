@@ -1,12 +1,229 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{MAX_MILESTONES, MAX_MILESTONE_NAME_LEN, MAX_UNLOCK_CHECKPOINTS},
+    errors::InvestmentDaoError,
+    math::{mul_div_floor, reaches_percentage},
+};
+
 #[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone)]
 pub struct VestingConfig {
-    pub cliff: u64,
-    pub period: i64,
     pub total_amount: u64,
     pub authority: Pubkey,
-    pub amount_per_period: u64,
+    pub schedule: VestingSchedule,
+}
+
+//Unlock curve of vested tokens. Step schedule is relative to vesting creation (proposal execution),
+//while other schedules use absolute timestamps
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone)]
+pub enum VestingSchedule {
+    //amount_per_period is unlocked after each period, once cliff (in seconds) has passed
+    Step {
+        cliff: u64,
+        period: i64,
+        amount_per_period: u64,
+    },
+    //Unlocked continuously (per second) between start and end
+    Linear {
+        start: i64,
+        end: i64,
+    },
+    //cliff_amount is unlocked at cliff_at, rest is unlocked linearly until end
+    CliffLinear {
+        cliff_at: i64,
+        cliff_amount: u64,
+        end: i64,
+    },
+    //Explicit (timestamp, cumulative amount) checkpoints, used for milestone deals
+    UnlockTable {
+        #[max_len(10)]
+        checkpoints: Vec<UnlockCheckpoint>,
+    },
+    //Tranche of each milestone is unlocked once milestone is approved
+    Milestones {
+        #[max_len(5)]
+        milestones: Vec<Milestone>,
+    },
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone)]
+pub struct UnlockCheckpoint {
+    pub timestamp: i64,
+    pub cumulative_amount: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone)]
+pub struct Milestone {
+    #[max_len(20)]
+    pub name: String,
+    pub amount: u64,
+    pub approval: MilestoneApproval,
+    pub approved_at: Option<i64>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, InitSpace, Clone, PartialEq)]
+pub enum MilestoneApproval {
+    //Attested by DAO authority with approve_milestone (or ApproveMilestone proposal, when DAO is governed)
+    Authority,
+    //Approved by members with cast_milestone_vote
+    MemberVote,
+}
+
+//Lightweight member vote on single milestone, created by first approval. There is no reject option or finalization,
+//milestone is approved as soon as approvals reach DAO approval threshold out of all shares at snapshot
+#[account]
+#[derive(InitSpace)]
+pub struct MilestoneVote {
+    pub dao: Pubkey,
+    pub vesting: Pubkey,
+    pub milestone_index: u8,
+    //Wallet that cast first approval, receives rent back once vote is closed
+    pub created_by: Pubkey,
+    pub snapshot_slot: u64,
+    pub max_voter_weight: u64,
+    pub approval_threshold: u8,
+    pub approvals_weight: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MilestoneVoteRecord {
+    pub authority: Pubkey,
+    pub dao: Pubkey,
+    pub milestone_vote: Pubkey,
+    pub voter_weight: u64,
+}
+
+impl MilestoneVote {
+    pub fn is_approved(&self) -> bool {
+        self.max_voter_weight > 0
+            && reaches_percentage(
+                self.approvals_weight,
+                self.max_voter_weight,
+                self.approval_threshold,
+            )
+    }
+}
+
+impl VestingConfig {
+    //Rejects configs that would lock tokens forever or break unlock math on claim
+    pub fn validate(&self) -> Result<()> {
+        let valid_schedule = match &self.schedule {
+            VestingSchedule::Step {
+                cliff,
+                period,
+                amount_per_period,
+            } => {
+                *cliff <= i64::MAX as u64
+                    && *period > 0
+                    && *amount_per_period > 0
+                    && *amount_per_period <= self.total_amount
+            }
+            VestingSchedule::Linear { start, end } => start < end,
+            VestingSchedule::CliffLinear {
+                cliff_at,
+                cliff_amount,
+                end,
+            } => cliff_at < end && *cliff_amount <= self.total_amount,
+            //Checkpoints are strictly increasing and last one unlocks total amount
+            VestingSchedule::UnlockTable { checkpoints } => {
+                !checkpoints.is_empty()
+                    && checkpoints.len() <= MAX_UNLOCK_CHECKPOINTS
+                    && checkpoints.windows(2).all(|pair| {
+                        pair[0].timestamp < pair[1].timestamp
+                            && pair[0].cumulative_amount < pair[1].cumulative_amount
+                    })
+                    && checkpoints.last().unwrap().cumulative_amount == self.total_amount
+            }
+            //Tranches add up to total amount and none is approved upfront
+            VestingSchedule::Milestones { milestones } => {
+                !milestones.is_empty()
+                    && milestones.len() <= MAX_MILESTONES
+                    && milestones.iter().all(|milestone| {
+                        !milestone.name.trim().is_empty()
+                            && milestone.name.len() <= MAX_MILESTONE_NAME_LEN
+                            && milestone.amount > 0
+                            && milestone.approved_at.is_none()
+                    })
+                    && milestones
+                        .iter()
+                        .try_fold(0u64, |sum, milestone| sum.checked_add(milestone.amount))
+                        == Some(self.total_amount)
+            }
+        };
+
+        require!(
+            self.total_amount > 0 && self.authority != Pubkey::default() && valid_schedule,
+            InvestmentDaoError::InvalidVestingConfig
+        );
+        Ok(())
+    }
+}
+
+impl VestingSchedule {
+    //Total amount unlocked until given timestamp, never more than total amount
+    pub fn vested_amount(&self, total_amount: u64, created_at: i64, now: i64) -> u64 {
+        let vested = match self {
+            VestingSchedule::Step {
+                cliff,
+                period,
+                amount_per_period,
+            } => {
+                let start_unlock = created_at.saturating_add(*cliff as i64);
+
+                if now <= start_unlock || *period <= 0 {
+                    return 0;
+                }
+
+                //Last period unlocks only remainder, when total amount isn't multiple of amount per period
+                (((now - start_unlock) / period) as u64)
+                    .saturating_mul(*amount_per_period)
+                    .min(total_amount)
+            }
+            VestingSchedule::Linear { start, end } => {
+                linear_amount(total_amount, *start, *end, now)
+            }
+            VestingSchedule::CliffLinear {
+                cliff_at,
+                cliff_amount,
+                end,
+            } => {
+                if now < *cliff_at {
+                    return 0;
+                }
+
+                let cliff_amount = (*cliff_amount).min(total_amount);
+
+                cliff_amount + linear_amount(total_amount - cliff_amount, *cliff_at, *end, now)
+            }
+            VestingSchedule::UnlockTable { checkpoints } => checkpoints
+                .iter()
+                .filter(|checkpoint| checkpoint.timestamp <= now)
+                .map(|checkpoint| checkpoint.cumulative_amount)
+                .max()
+                .unwrap_or(0),
+            VestingSchedule::Milestones { milestones } => milestones
+                .iter()
+                .filter(|milestone| matches!(milestone.approved_at, Some(approved_at) if approved_at <= now))
+                .fold(0u64, |sum, milestone| sum.saturating_add(milestone.amount)),
+        };
+
+        vested.min(total_amount)
+    }
+}
+
+//Amount unlocked linearly between start and end, rounded down
+fn linear_amount(amount: u64, start: i64, end: i64, now: i64) -> u64 {
+    if now <= start {
+        return 0;
+    }
+
+    if now >= end {
+        return amount;
+    }
+
+    //now - start < end - start, so result always fits
+    mul_div_floor(amount, (now - start) as u64, (end - start) as u64).unwrap_or(amount)
 }
 
 #[account]
@@ -15,7 +232,501 @@ pub struct Vesting {
     pub config: VestingConfig,
     pub created_at: i64,
     pub proposal: Pubkey,
+    pub dao: Pubkey,
     pub total_claimed: u64,
     pub last_claim_at: i64,
     pub remaining_amount: u64,
+    //Set by governance clawback, nothing unlocks afterwards
+    pub revoked_at: Option<i64>,
+}
+
+impl Vesting {
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let now = match self.revoked_at {
+            Some(revoked_at) => now.min(revoked_at),
+            None => now,
+        };
+
+        self.config
+            .schedule
+            .vested_amount(self.config.total_amount, self.created_at, now)
+    }
+
+    //Newly vested amount, capped at amount left in vesting treasury (which can be lower than total amount
+    //if transfer fee was charged on funding)
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now)
+            .saturating_sub(self.total_claimed)
+            .min(self.remaining_amount)
+    }
+
+    //Milestone can be approved only once, through its approval path, while vesting isn't revoked
+    pub fn check_milestone_approval(
+        &self,
+        milestone_index: u8,
+        approval: MilestoneApproval,
+    ) -> Result<()> {
+        require!(
+            self.revoked_at.is_none(),
+            InvestmentDaoError::VestingAlreadyRevoked
+        );
+
+        let milestone = match &self.config.schedule {
+            VestingSchedule::Milestones { milestones } => milestones.get(milestone_index as usize),
+            _ => None,
+        }
+        .ok_or(error!(InvestmentDaoError::InvalidMilestone))?;
+
+        require!(
+            milestone.approval == approval,
+            InvestmentDaoError::InvalidMilestoneApproval
+        );
+        require!(
+            milestone.approved_at.is_none(),
+            InvestmentDaoError::MilestoneAlreadyApproved
+        );
+        Ok(())
+    }
+
+    pub fn approve_milestone(
+        &mut self,
+        milestone_index: u8,
+        approval: MilestoneApproval,
+        now: i64,
+    ) -> Result<()> {
+        self.check_milestone_approval(milestone_index, approval)?;
+
+        if let VestingSchedule::Milestones { milestones } = &mut self.config.schedule {
+            milestones[milestone_index as usize].approved_at = Some(now);
+        }
+        Ok(())
+    }
+
+    //Amount returned to DAO treasury on revocation, vested but unclaimed tokens stay claimable
+    pub fn unvested_amount(&self, now: i64) -> u64 {
+        self.remaining_amount - self.claimable_amount(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting(total_amount: u64, schedule: VestingSchedule) -> Vesting {
+        Vesting {
+            config: VestingConfig {
+                total_amount,
+                authority: Pubkey::default(),
+                schedule,
+            },
+            created_at: 1_000,
+            proposal: Pubkey::default(),
+            dao: Pubkey::default(),
+            total_claimed: 0,
+            last_claim_at: 0,
+            remaining_amount: total_amount,
+            revoked_at: None,
+        }
+    }
+
+    fn step_vesting(total_amount: u64, amount_per_period: u64) -> Vesting {
+        vesting(
+            total_amount,
+            VestingSchedule::Step {
+                cliff: 100,
+                period: 10,
+                amount_per_period,
+            },
+        )
+    }
+
+    //Simulates claim_tokens accounting
+    fn claim(vesting: &mut Vesting, now: i64) -> u64 {
+        let amount = vesting.claimable_amount(now);
+        vesting.total_claimed += amount;
+        vesting.remaining_amount -= amount;
+        amount
+    }
+
+    #[test]
+    fn repeated_claims_pay_only_new_periods() {
+        let mut vesting = step_vesting(100, 30);
+
+        assert_eq!(claim(&mut vesting, 1_100), 0);
+        assert_eq!(claim(&mut vesting, 1_110), 30);
+        assert_eq!(claim(&mut vesting, 1_115), 0);
+        assert_eq!(claim(&mut vesting, 1_125), 30);
+        //Last period settles remainder of 10
+        assert_eq!(claim(&mut vesting, 1_200), 40);
+        assert_eq!(claim(&mut vesting, 2_000), 0);
+
+        assert_eq!(vesting.total_claimed, 100);
+        assert_eq!(vesting.remaining_amount, 0);
+    }
+
+    #[test]
+    fn claims_are_capped_at_funded_amount() {
+        let mut vesting = step_vesting(100, 30);
+        //Treasury received less than total amount because of transfer fee
+        vesting.remaining_amount = 95;
+
+        assert_eq!(claim(&mut vesting, 1_120), 60);
+        assert_eq!(claim(&mut vesting, 1_200), 35);
+        assert_eq!(vesting.remaining_amount, 0);
+    }
+
+    //Simulates execution of RevokeVesting proposal
+    fn revoke(vesting: &mut Vesting, now: i64) -> u64 {
+        let amount = vesting.unvested_amount(now);
+        vesting.remaining_amount -= amount;
+        vesting.revoked_at = Some(now);
+        amount
+    }
+
+    #[test]
+    fn revoked_vesting_keeps_only_vested_amount() {
+        let mut vesting = step_vesting(100, 30);
+
+        assert_eq!(claim(&mut vesting, 1_110), 30);
+        //Second period vested, but wasn't claimed before revocation
+        assert_eq!(revoke(&mut vesting, 1_125), 40);
+        assert_eq!(vesting.remaining_amount, 30);
+
+        assert_eq!(claim(&mut vesting, 2_000), 30);
+        assert_eq!(claim(&mut vesting, 3_000), 0);
+        assert_eq!(vesting.remaining_amount, 0);
+    }
+
+    #[test]
+    fn step_schedule_doesnt_overflow_after_long_time() {
+        let mut vesting = step_vesting(u64::MAX, u64::MAX / 2);
+
+        assert_eq!(vesting.vested_amount(i64::MAX), u64::MAX);
+        assert_eq!(claim(&mut vesting, i64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn linear_schedule() {
+        let schedule = VestingSchedule::Linear {
+            start: 2_000,
+            end: 3_000,
+        };
+
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 1_999), 0);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_000), 0);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_001), 1);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_500), 500);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 3_000), 1_000);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, i64::MAX), 1_000);
+        //Rounded down until the end
+        assert_eq!(schedule.vested_amount(10, 1_000, 2_999), 9);
+    }
+
+    #[test]
+    fn cliff_linear_schedule() {
+        let schedule = VestingSchedule::CliffLinear {
+            cliff_at: 2_000,
+            cliff_amount: 400,
+            end: 3_000,
+        };
+
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 1_999), 0);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_000), 400);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_500), 700);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 3_000), 1_000);
+
+        //Cliff amount can't exceed total amount
+        assert_eq!(schedule.vested_amount(100, 1_000, 2_000), 100);
+    }
+
+    #[test]
+    fn unlock_table_schedule() {
+        let schedule = VestingSchedule::UnlockTable {
+            checkpoints: vec![
+                UnlockCheckpoint {
+                    timestamp: 2_000,
+                    cumulative_amount: 250,
+                },
+                UnlockCheckpoint {
+                    timestamp: 5_000,
+                    cumulative_amount: 600,
+                },
+                UnlockCheckpoint {
+                    timestamp: 9_000,
+                    cumulative_amount: 1_000,
+                },
+            ],
+        };
+
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 1_999), 0);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 2_000), 250);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 4_999), 250);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 5_000), 600);
+        assert_eq!(schedule.vested_amount(1_000, 1_000, 10_000), 1_000);
+        //Table is capped at total amount
+        assert_eq!(schedule.vested_amount(500, 1_000, 5_000), 500);
+    }
+
+    fn config(total_amount: u64, schedule: VestingSchedule) -> VestingConfig {
+        VestingConfig {
+            total_amount,
+            authority: Pubkey::new_unique(),
+            schedule,
+        }
+    }
+
+    fn checkpoint(timestamp: i64, cumulative_amount: u64) -> UnlockCheckpoint {
+        UnlockCheckpoint {
+            timestamp,
+            cumulative_amount,
+        }
+    }
+
+    #[test]
+    fn validates_vesting_config() {
+        let step = |period, amount_per_period| VestingSchedule::Step {
+            cliff: 0,
+            period,
+            amount_per_period,
+        };
+
+        assert!(config(100, step(10, 30)).validate().is_ok());
+        assert!(config(100, step(0, 30)).validate().is_err());
+        assert!(config(100, step(10, 0)).validate().is_err());
+        assert!(config(100, step(10, 101)).validate().is_err());
+        assert!(config(0, step(10, 30)).validate().is_err());
+
+        let mut no_authority = config(100, step(10, 30));
+        no_authority.authority = Pubkey::default();
+        assert!(no_authority.validate().is_err());
+
+        let linear = |start, end| VestingSchedule::Linear { start, end };
+        assert!(config(100, linear(10, 20)).validate().is_ok());
+        assert!(config(100, linear(20, 20)).validate().is_err());
+
+        let cliff_linear = |cliff_amount| VestingSchedule::CliffLinear {
+            cliff_at: 10,
+            cliff_amount,
+            end: 20,
+        };
+        assert!(config(100, cliff_linear(100)).validate().is_ok());
+        assert!(config(100, cliff_linear(101)).validate().is_err());
+
+        let table = |checkpoints| VestingSchedule::UnlockTable { checkpoints };
+        assert!(
+            config(100, table(vec![checkpoint(10, 40), checkpoint(20, 100)]))
+                .validate()
+                .is_ok()
+        );
+        assert!(config(100, table(vec![])).validate().is_err());
+        //Not everything gets unlocked
+        assert!(
+            config(100, table(vec![checkpoint(10, 40), checkpoint(20, 90)]))
+                .validate()
+                .is_err()
+        );
+        //Unordered timestamps
+        assert!(
+            config(100, table(vec![checkpoint(20, 40), checkpoint(10, 100)]))
+                .validate()
+                .is_err()
+        );
+        assert!(config(
+            110,
+            table((1..=11).map(|i| checkpoint(i, i as u64 * 10)).collect())
+        )
+        .validate()
+        .is_err());
+    }
+
+    fn milestone(amount: u64, approval: MilestoneApproval) -> Milestone {
+        Milestone {
+            name: "Milestone".to_string(),
+            amount,
+            approval,
+            approved_at: None,
+        }
+    }
+
+    fn milestone_vesting() -> Vesting {
+        vesting(
+            100,
+            VestingSchedule::Milestones {
+                milestones: vec![
+                    milestone(30, MilestoneApproval::Authority),
+                    milestone(70, MilestoneApproval::MemberVote),
+                ],
+            },
+        )
+    }
+
+    #[test]
+    fn milestones_unlock_on_approval() {
+        let mut vesting = milestone_vesting();
+
+        assert!(vesting.config.validate().is_err());
+        vesting.config.authority = Pubkey::new_unique();
+        assert!(vesting.config.validate().is_ok());
+
+        assert_eq!(claim(&mut vesting, 5_000), 0);
+
+        //Second tranche gets unlocked first
+        vesting
+            .approve_milestone(1, MilestoneApproval::MemberVote, 5_000)
+            .unwrap();
+        assert_eq!(claim(&mut vesting, 5_000), 70);
+
+        vesting
+            .approve_milestone(0, MilestoneApproval::Authority, 6_000)
+            .unwrap();
+        assert_eq!(claim(&mut vesting, 6_000), 30);
+        assert_eq!(vesting.remaining_amount, 0);
+        //Tranches need to add up to total amount
+        let mut config = milestone_vesting().config;
+        config.authority = Pubkey::new_unique();
+        config.total_amount = 90;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn milestone_approval_checks() {
+        let fresh = milestone_vesting();
+        let approved = {
+            let mut vesting = milestone_vesting();
+            vesting
+                .approve_milestone(0, MilestoneApproval::Authority, 5_000)
+                .unwrap();
+            vesting
+        };
+        //Unapproved tranche is returned on revocation, approved one stays claimable
+        let revoked = {
+            let mut vesting = approved.clone();
+            assert_eq!(revoke(&mut vesting, 6_000), 70);
+            assert_eq!(claim(&mut vesting, 7_000), 30);
+            vesting
+        };
+        let step = step_vesting(100, 10);
+
+        let cases = [
+            (
+                &fresh,
+                0,
+                MilestoneApproval::MemberVote,
+                Some(InvestmentDaoError::InvalidMilestoneApproval),
+            ),
+            (&fresh, 0, MilestoneApproval::Authority, None),
+            (
+                &approved,
+                0,
+                MilestoneApproval::Authority,
+                Some(InvestmentDaoError::MilestoneAlreadyApproved),
+            ),
+            (&approved, 1, MilestoneApproval::MemberVote, None),
+            (
+                &approved,
+                1,
+                MilestoneApproval::Authority,
+                Some(InvestmentDaoError::InvalidMilestoneApproval),
+            ),
+            (
+                &approved,
+                2,
+                MilestoneApproval::MemberVote,
+                Some(InvestmentDaoError::InvalidMilestone),
+            ),
+            (
+                &approved,
+                u8::MAX,
+                MilestoneApproval::Authority,
+                Some(InvestmentDaoError::InvalidMilestone),
+            ),
+            (
+                &revoked,
+                1,
+                MilestoneApproval::MemberVote,
+                Some(InvestmentDaoError::VestingAlreadyRevoked),
+            ),
+            (
+                &step,
+                0,
+                MilestoneApproval::MemberVote,
+                Some(InvestmentDaoError::InvalidMilestone),
+            ),
+        ];
+
+        for (vesting, milestone_index, approval, expected) in cases {
+            let expected = match expected {
+                Some(err) => Err(error!(err)),
+                None => Ok(()),
+            };
+
+            assert_eq!(
+                vesting.check_milestone_approval(milestone_index, approval.clone()),
+                expected,
+                "milestone {}",
+                milestone_index
+            );
+            //Approval is applied exactly when check passes
+            assert_eq!(
+                vesting
+                    .clone()
+                    .approve_milestone(milestone_index, approval, 5_000),
+                expected,
+                "milestone {}",
+                milestone_index
+            );
+        }
+    }
+
+    #[test]
+    fn milestone_vote_needs_threshold_of_all_shares() {
+        let milestone_vote = |max_voter_weight, approvals_weight| MilestoneVote {
+            dao: Pubkey::new_unique(),
+            vesting: Pubkey::new_unique(),
+            milestone_index: 0,
+            created_by: Pubkey::new_unique(),
+            snapshot_slot: 10,
+            max_voter_weight,
+            approval_threshold: 60,
+            approvals_weight,
+        };
+
+        assert!(!milestone_vote(1_000, 599).is_approved());
+        assert!(milestone_vote(1_000, 600).is_approved());
+        //DAO without shares can't approve anything
+        assert!(!milestone_vote(0, 0).is_approved());
+    }
+
+    #[test]
+    fn vested_amount_is_monotonic_and_bounded() {
+        let schedules = vec![
+            VestingSchedule::Step {
+                cliff: 50,
+                period: 7,
+                amount_per_period: 33,
+            },
+            VestingSchedule::Linear {
+                start: 1_100,
+                end: 1_900,
+            },
+            VestingSchedule::CliffLinear {
+                cliff_at: 1_300,
+                cliff_amount: 123,
+                end: 1_700,
+            },
+        ];
+
+        for schedule in schedules {
+            let mut previous = 0;
+
+            for now in 900..2_100 {
+                let vested = schedule.vested_amount(1_000, 1_000, now);
+
+                assert!(vested >= previous && vested <= 1_000);
+                previous = vested;
+            }
+
+            assert_eq!(previous, 1_000);
+        }
+    }
 }
@@ -1,2 +1,575 @@
-use crate::state::{Currency, InvestmentDao};
-use anchor_lang::prelude::*;
+use crate::{
+    constants::{DAO_SHARE_MINT_SEED, INVESTMENT_DAO_TREASURY_SEED},
+    errors::InvestmentDaoError,
+    math::mul_div_floor,
+    state::{Currency, InvestmentDao, TreasuryAsset},
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program_option::COption},
+    system_program::{create_account, CreateAccount},
+};
+use anchor_spl::{
+    token::TokenAccount,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+    },
+    token_interface,
+};
+
+//Mints shares to investor share account (ATA of share mint). Share accounts are kept frozen and delegated to share
+//mint, so shares can be moved or burned only by program
+pub fn mint_shares<'info>(
+    investment_dao: &Account<'info, InvestmentDao>,
+    share_mint: &AccountInfo<'info>,
+    share_mint_bump: u8,
+    investor_share_account: &Account<'info, TokenAccount>,
+    investor: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    shares: u64,
+) -> Result<()> {
+    let investment_dao_key = investment_dao.key();
+    let share_mint_seeds: &[&[&[u8]]] = &[&[
+        DAO_SHARE_MINT_SEED,
+        investment_dao_key.as_ref(),
+        &[share_mint_bump],
+    ]];
+
+    thaw_share_account(
+        share_mint,
+        investor_share_account,
+        token_program,
+        share_mint_seeds,
+    )?;
+
+    anchor_spl::token::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            anchor_spl::token::MintTo {
+                mint: share_mint.clone(),
+                to: investor_share_account.to_account_info(),
+                authority: share_mint.clone(),
+            },
+            share_mint_seeds,
+        ),
+        shares,
+    )?;
+
+    //Delegation lets program burn shares when member is removed from DAO
+    anchor_spl::token::approve(
+        CpiContext::new(
+            token_program.clone(),
+            anchor_spl::token::Approve {
+                to: investor_share_account.to_account_info(),
+                delegate: share_mint.clone(),
+                authority: investor.clone(),
+            },
+        ),
+        u64::MAX,
+    )?;
+
+    freeze_share_account(
+        share_mint,
+        investor_share_account,
+        token_program,
+        share_mint_seeds,
+    )
+}
+
+//Burns shares from (frozen) share account, using share mint as delegate
+pub fn burn_shares<'info>(
+    investment_dao_key: &Pubkey,
+    share_mint: &AccountInfo<'info>,
+    share_mint_bump: u8,
+    share_account: &Account<'info, TokenAccount>,
+    token_program: &AccountInfo<'info>,
+    shares: u64,
+) -> Result<()> {
+    let share_mint_seeds: &[&[&[u8]]] = &[&[
+        DAO_SHARE_MINT_SEED,
+        investment_dao_key.as_ref(),
+        &[share_mint_bump],
+    ]];
+
+    thaw_share_account(share_mint, share_account, token_program, share_mint_seeds)?;
+
+    anchor_spl::token::burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            anchor_spl::token::Burn {
+                mint: share_mint.clone(),
+                from: share_account.to_account_info(),
+                authority: share_mint.clone(),
+            },
+            share_mint_seeds,
+        ),
+        shares,
+    )?;
+
+    freeze_share_account(share_mint, share_account, token_program, share_mint_seeds)
+}
+
+//Moves shares between two share accounts, using share mint as delegate of sender account
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_shares<'info>(
+    investment_dao_key: &Pubkey,
+    share_mint: &AccountInfo<'info>,
+    share_mint_bump: u8,
+    from: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    recipient: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    shares: u64,
+) -> Result<()> {
+    let share_mint_seeds: &[&[&[u8]]] = &[&[
+        DAO_SHARE_MINT_SEED,
+        investment_dao_key.as_ref(),
+        &[share_mint_bump],
+    ]];
+
+    thaw_share_account(share_mint, from, token_program, share_mint_seeds)?;
+    thaw_share_account(share_mint, to, token_program, share_mint_seeds)?;
+
+    //Recipient that never deposited has not delegated share account to program yet
+    if to.delegate != COption::Some(share_mint.key()) {
+        anchor_spl::token::approve(
+            CpiContext::new(
+                token_program.clone(),
+                anchor_spl::token::Approve {
+                    to: to.to_account_info(),
+                    delegate: share_mint.clone(),
+                    authority: recipient.clone(),
+                },
+            ),
+            u64::MAX,
+        )?;
+    }
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            anchor_spl::token::Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: share_mint.clone(),
+            },
+            share_mint_seeds,
+        ),
+        shares,
+    )?;
+
+    freeze_share_account(share_mint, from, token_program, share_mint_seeds)?;
+    freeze_share_account(share_mint, to, token_program, share_mint_seeds)
+}
+
+fn thaw_share_account<'info>(
+    share_mint: &AccountInfo<'info>,
+    share_account: &Account<'info, TokenAccount>,
+    token_program: &AccountInfo<'info>,
+    share_mint_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if !share_account.is_frozen() {
+        return Ok(());
+    }
+
+    anchor_spl::token::thaw_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        anchor_spl::token::ThawAccount {
+            account: share_account.to_account_info(),
+            mint: share_mint.clone(),
+            authority: share_mint.clone(),
+        },
+        share_mint_seeds,
+    ))
+}
+
+fn freeze_share_account<'info>(
+    share_mint: &AccountInfo<'info>,
+    share_account: &Account<'info, TokenAccount>,
+    token_program: &AccountInfo<'info>,
+    share_mint_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    anchor_spl::token::freeze_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        anchor_spl::token::FreezeAccount {
+            account: share_account.to_account_info(),
+            mint: share_mint.clone(),
+            authority: share_mint.clone(),
+        },
+        share_mint_seeds,
+    ))
+}
+
+//Transfers pro-rata share of DAO treasury to leaving member and burns his shares.
+//Receiver is member wallet for SOL DAOs, or token account (with currency mint) owned by member for SPL DAOs.
+//Member also receives pro-rata share of each registered treasury asset, passed in treasury_assets as
+//(treasury asset, asset treasury, member token account, asset mint, asset token program)
+#[allow(clippy::too_many_arguments)]
+pub fn pay_out_member<'info>(
+    investment_dao: &mut Account<'info, InvestmentDao>,
+    investor: &Pubkey,
+    treasury_assets: &[AccountInfo<'info>],
+    dao_treasury: &AccountInfo<'info>,
+    treasury_bump: u8,
+    share_mint: &AccountInfo<'info>,
+    share_account: &Account<'info, TokenAccount>,
+    shares: u64,
+    deposit_amount: u64,
+    receiver: &AccountInfo<'info>,
+    currency_mint: Option<&AccountInfo<'info>>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    currency_token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    require!(
+        share_mint.key() == investment_dao.share_mint,
+        InvestmentDaoError::InvalidShareMint
+    );
+
+    let treasury_balance = match investment_dao.currency {
+        Currency::Sol => {
+            require!(
+                receiver.key() == *investor,
+                InvestmentDaoError::InvalidInvestorWallet
+            );
+
+            dao_treasury.lamports()
+        }
+        Currency::Spl => {
+            let receiver_token =
+                InterfaceAccount::<token_interface::TokenAccount>::try_from(receiver)?;
+
+            require!(
+                receiver_token.mint == investment_dao.denominated_currency,
+                InvestmentDaoError::InvalidDepositMint
+            );
+            require!(
+                receiver_token.owner == *investor,
+                InvestmentDaoError::InvalidInvestorWallet
+            );
+
+            token_balance(dao_treasury)?
+        }
+    };
+
+    //Rounded down, so treasury is never over-distributed to leaving members
+    let payout = if investment_dao.total_shares == 0 {
+        0
+    } else {
+        mul_div_floor(treasury_balance, shares, investment_dao.total_shares)?
+    };
+
+    pay_out_treasury_assets(investment_dao, investor, shares, treasury_assets)?;
+
+    investment_dao.total_shares = investment_dao.total_shares.checked_sub(shares).unwrap();
+    investment_dao.total_deposited = investment_dao
+        .total_deposited
+        .checked_sub(deposit_amount)
+        .unwrap();
+    //DAO creator is not counted in investors_count
+    if *investor != investment_dao.creator {
+        investment_dao.investors_count = investment_dao.investors_count.saturating_sub(1);
+    }
+
+    let investment_dao_key = investment_dao.key();
+
+    if shares > 0 {
+        let (_, share_mint_bump) =
+            InvestmentDao::get_share_mint_address(&investment_dao_key, &crate::ID);
+
+        burn_shares(
+            &investment_dao_key,
+            share_mint,
+            share_mint_bump,
+            share_account,
+            token_program,
+            shares,
+        )?;
+    }
+
+    if payout == 0 {
+        return Ok(0);
+    }
+
+    let treasury_seeds: &[&[&[u8]]] = &[&[
+        INVESTMENT_DAO_TREASURY_SEED,
+        investment_dao_key.as_ref(),
+        investment_dao.denominated_currency.as_ref(),
+        &[treasury_bump],
+    ]];
+
+    match investment_dao.currency {
+        Currency::Sol => {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: dao_treasury.clone(),
+                        to: receiver.clone(),
+                    },
+                    treasury_seeds,
+                ),
+                payout,
+            )?;
+        }
+        Currency::Spl => {
+            let currency_mint = currency_mint.ok_or(InvestmentDaoError::InvalidDepositMint)?;
+
+            transfer_currency(
+                dao_treasury,
+                receiver,
+                dao_treasury,
+                currency_mint,
+                currency_token_program,
+                payout,
+                treasury_seeds,
+            )?;
+        }
+    }
+
+    Ok(payout)
+}
+
+fn pay_out_treasury_assets<'info>(
+    investment_dao: &Account<'info, InvestmentDao>,
+    investor: &Pubkey,
+    shares: u64,
+    treasury_assets: &[AccountInfo<'info>],
+) -> Result<()> {
+    require!(
+        treasury_assets.len() == investment_dao.treasury_assets_count as usize * 5,
+        InvestmentDaoError::MissingTreasuryAssets
+    );
+
+    let investment_dao_key = investment_dao.key();
+    let mut paid_mints: Vec<Pubkey> = vec![];
+
+    for accounts in treasury_assets.chunks(5) {
+        let (asset_treasury, receiver, asset_mint, asset_token_program) =
+            (&accounts[1], &accounts[2], &accounts[3], &accounts[4]);
+
+        let mut treasury_asset = load_treasury_asset(
+            investment_dao,
+            &accounts[0],
+            asset_treasury,
+            &mut paid_mints,
+        )?;
+
+        require!(
+            asset_mint.key() == treasury_asset.mint
+                && asset_token_program.key() == *asset_mint.owner,
+            InvestmentDaoError::InvalidTreasuryAsset
+        );
+
+        let receiver_token = InterfaceAccount::<token_interface::TokenAccount>::try_from(receiver)?;
+
+        require!(
+            receiver_token.mint == treasury_asset.mint && receiver_token.owner == *investor,
+            InvestmentDaoError::InvalidInvestorWallet
+        );
+
+        let payout = if investment_dao.total_shares == 0 {
+            0
+        } else {
+            mul_div_floor(
+                token_balance(asset_treasury)?,
+                shares,
+                investment_dao.total_shares,
+            )?
+        };
+
+        if payout > 0 {
+            let bump = InvestmentDao::check_treasury_seeds(
+                asset_treasury,
+                &investment_dao_key,
+                treasury_asset.mint,
+                &crate::ID,
+            )?;
+
+            transfer_currency(
+                asset_treasury,
+                receiver,
+                asset_treasury,
+                asset_mint,
+                asset_token_program,
+                payout,
+                &[&[
+                    INVESTMENT_DAO_TREASURY_SEED,
+                    investment_dao_key.as_ref(),
+                    treasury_asset.mint.as_ref(),
+                    &[bump],
+                ]],
+            )?;
+        }
+
+        treasury_asset.balance = token_balance(asset_treasury)?;
+        treasury_asset.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+//Value of registered treasury assets in denominated currency, at prices stored in treasury assets (assets without price
+//don't add to NAV). Treasury assets are passed as (treasury asset, asset treasury)
+pub fn treasury_assets_value<'info>(
+    investment_dao: &Account<'info, InvestmentDao>,
+    treasury_assets: &[AccountInfo<'info>],
+) -> Result<u64> {
+    require!(
+        treasury_assets.len() == investment_dao.treasury_assets_count as usize * 2,
+        InvestmentDaoError::MissingTreasuryAssets
+    );
+
+    let mut priced_mints: Vec<Pubkey> = vec![];
+    let mut total_value: u64 = 0;
+
+    for accounts in treasury_assets.chunks(2) {
+        let treasury_asset = load_treasury_asset(
+            investment_dao,
+            &accounts[0],
+            &accounts[1],
+            &mut priced_mints,
+        )?;
+
+        total_value = total_value
+            .checked_add(treasury_asset.value(token_balance(&accounts[1])?)?)
+            .ok_or(error!(InvestmentDaoError::MathOverflow))?;
+    }
+
+    Ok(total_value)
+}
+
+//Each registered asset of DAO can be passed only once
+fn load_treasury_asset<'info>(
+    investment_dao: &Account<'info, InvestmentDao>,
+    raw_treasury_asset: &AccountInfo<'info>,
+    asset_treasury: &AccountInfo<'info>,
+    loaded_mints: &mut Vec<Pubkey>,
+) -> Result<Account<'info, TreasuryAsset>> {
+    let treasury_asset = Account::<TreasuryAsset>::try_from(raw_treasury_asset)?;
+
+    require!(
+        treasury_asset.dao == investment_dao.key()
+            && treasury_asset.treasury == asset_treasury.key()
+            && !loaded_mints.contains(&treasury_asset.mint),
+        InvestmentDaoError::InvalidTreasuryAsset
+    );
+
+    loaded_mints.push(treasury_asset.mint);
+    Ok(treasury_asset)
+}
+
+//Creates token account at PDA address, with PDA as its own authority. Account size is queried from
+//token program, so it covers extensions required by Token-2022 mints (e.g. transfer fee amount)
+pub fn create_treasury_token_account<'info>(
+    payer: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    treasury_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let space = if token_program.key() == token_interface::ID {
+        token_interface::get_account_data_size(
+            CpiContext::new(
+                token_program.clone(),
+                token_interface::GetAccountDataSize { mint: mint.clone() },
+            ),
+            &[],
+        )?
+    } else {
+        TokenAccount::LEN as u64
+    };
+
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: treasury.clone(),
+            },
+            treasury_seeds,
+        ),
+        Rent::default().minimum_balance(space as usize),
+        space,
+        token_program.key,
+    )?;
+
+    token_interface::initialize_account3(CpiContext::new(
+        token_program.clone(),
+        token_interface::InitializeAccount3 {
+            account: treasury.clone(),
+            mint: mint.clone(),
+            authority: treasury.clone(),
+        },
+    ))
+}
+
+//Transfers denominated (or vesting) tokens with mint decimals, works for both token programs
+pub fn transfer_currency<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let decimals = InterfaceAccount::<token_interface::Mint>::try_from(mint)?.decimals;
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token_interface::TransferChecked {
+                from: from.clone(),
+                mint: mint.clone(),
+                to: to.clone(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
+
+pub fn token_balance(token_account: &AccountInfo) -> Result<u64> {
+    Ok(InterfaceAccount::<token_interface::TokenAccount>::try_from(token_account)?.amount)
+}
+
+//Closes drained treasury token account. Transfer fees withheld in Token-2022 account are harvested
+//to mint first, since account with withheld fees can't be closed
+pub fn close_treasury_token_account<'info>(
+    treasury: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    treasury_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if token_program.key() == token_interface::ID && has_transfer_fee(mint)? {
+        let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[treasury.key])?;
+
+        invoke(&ix, &[mint.clone(), treasury.clone()])?;
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        token_interface::CloseAccount {
+            account: treasury.clone(),
+            destination: destination.clone(),
+            authority: treasury.clone(),
+        },
+        treasury_seeds,
+    ))
+}
+
+fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    Ok(mint_state.get_extension::<TransferFeeConfig>().is_ok())
+}
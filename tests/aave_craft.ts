@@ -1,14 +1,15 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { Connection, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Connection, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
 import { AaveCraft } from "../target/types/aave_craft";
 import { DaoAction, ProposalType, VoteOption } from "./constants";
 import {
-  getAccountSolBalance,
   getActionLog,
   getKeypair,
   getLog,
   sendAndConfirmTransaction,
+  sleep,
 } from "./helpers";
 import { Dao } from "./models/dao";
 import { Proposal } from "./models/proposal";
@@ -26,119 +27,89 @@ describe("aave_craft", () => {
 
     const dao = new Dao(authority, program, 20, 51);
 
-    const createDaoIx = await dao.createDao();
+    getActionLog(`Creating dao`);
+    await sendAndConfirmTransaction([await dao.createDao()], connection, [
+      authority,
+    ]);
 
-    try {
-      getActionLog(`Creating dao`);
-      await sendAndConfirmTransaction([createDaoIx], connection, [authority]);
-
-      getLog(`Created DAO with name: ${dao.name}`);
-      getLog(`DAO authority: ${dao.authority.publicKey.toString()}`);
-    } catch (error) {
-      console.log(error);
-    }
+    const daoData = await dao.getDao();
+    expect(daoData.authority.toString()).to.equal(
+      authority.publicKey.toString()
+    );
+    getLog(`Created DAO with name: ${dao.name}`);
 
     const daoMember1 = await getKeypair(connection);
     const daoMember2 = await getKeypair(connection);
 
-    try {
-      getActionLog("Inviting 2 DAO members");
-      const ix1 = await dao.inviteDaoMember(daoMember1.publicKey);
-      const ix2 = await dao.inviteDaoMember(daoMember2.publicKey);
+    getActionLog("Inviting 2 DAO members");
+    await sendAndConfirmTransaction(
+      [
+        await dao.inviteDaoMember(daoMember1.publicKey),
+        await dao.inviteDaoMember(daoMember2.publicKey),
+      ],
+      connection,
+      [authority]
+    );
 
-      await sendAndConfirmTransaction([ix1, ix2], connection, [authority]);
-      getLog(`Invited DAO member: ${daoMember1.publicKey}`);
-      getLog(`Invited DAO member: ${daoMember2.publicKey}`);
-    } catch (error) {
-      console.log(error);
-    }
+    expect(
+      (await dao.getInvestorData(daoMember1.publicKey)).state
+    ).to.deep.equal({ invited: {} });
 
-    try {
-      getActionLog("Accepting dao memberships");
-      const ix1 = await dao.acceptOrRejectDaoMembership(
-        daoMember1,
-        DaoAction.Accept
-      );
-      const ix2 = await dao.acceptOrRejectDaoMembership(
-        daoMember2,
-        DaoAction.Accept
-      );
-      await sendAndConfirmTransaction([ix1], connection, [daoMember1]);
-      await sendAndConfirmTransaction([ix2], connection, [daoMember2]);
+    getActionLog("Accepting dao memberships");
+    await sendAndConfirmTransaction(
+      [await dao.acceptOrRejectDaoMembership(daoMember1, DaoAction.Accept)],
+      connection,
+      [daoMember1]
+    );
+    await sendAndConfirmTransaction(
+      [await dao.acceptOrRejectDaoMembership(daoMember2, DaoAction.Accept)],
+      connection,
+      [daoMember2]
+    );
 
-      getLog(
-        `Investor ${daoMember1.publicKey.toString()} accepted memberhip in ${
-          dao.name
-        }`
-      );
-      getLog(
-        `Investor ${daoMember2.publicKey.toString()} accepted memberhip in ${
-          dao.name
-        }`
+    for (const member of [daoMember1, daoMember2]) {
+      expect((await dao.getInvestorData(member.publicKey)).state).to.deep.equal(
+        { accepted: {} }
       );
-    } catch (error) {
-      console.log(error);
     }
+    expect((await dao.getDao()).investorsCount).to.equal(2);
 
-    try {
-      getActionLog(`Depositing to DAO treasury`);
-      const ix1 = await dao.depositToDao(
-        2 * LAMPORTS_PER_SOL,
-        daoMember1.publicKey
-      );
-      const ix2 = await dao.depositToDao(
-        3 * LAMPORTS_PER_SOL,
-        daoMember2.publicKey
-      );
-
-      const ix3 = await dao.depositToDao(
-        1 * LAMPORTS_PER_SOL,
-        authority.publicKey
-      );
-      await sendAndConfirmTransaction([ix1], connection, [daoMember1]);
-      await sendAndConfirmTransaction([ix2], connection, [daoMember2]);
-      await sendAndConfirmTransaction([ix3], connection, [authority]);
-
-      const fr1 = await dao.getFinancialRecord(daoMember1.publicKey);
-      const fr2 = await dao.getFinancialRecord(daoMember2.publicKey);
-      const fr3 = await dao.getFinancialRecord(authority.publicKey);
-
-      const treasurySolBalance = await dao.getTreasurySolBalance(connection);
-      getLog(
-        `Investor ${daoMember1.publicKey} invested ${
-          fr1.totalDepositAmount.toNumber() / LAMPORTS_PER_SOL
-        } SOL at timestamp: ${fr1.lastDepositAt.toNumber()} and has ${(
-          (fr1.totalDepositAmount.toNumber() /
-            (treasurySolBalance * LAMPORTS_PER_SOL)) *
-          100
-        ).toFixed(2)} stake %`
-      );
-
-      getLog(
-        `Investor ${daoMember2.publicKey} invested ${
-          fr2.totalDepositAmount.toNumber() / LAMPORTS_PER_SOL
-        } SOL at timestamp: ${fr2.lastDepositAt.toNumber()}  and has ${(
-          (fr2.totalDepositAmount.toNumber() /
-            (treasurySolBalance * LAMPORTS_PER_SOL)) *
-          100
-        ).toFixed(2)} stake %`
-      );
+    getActionLog(`Depositing to DAO treasury`);
+    await sendAndConfirmTransaction(
+      await dao.depositToDao(2 * LAMPORTS_PER_SOL, daoMember1.publicKey),
+      connection,
+      [daoMember1]
+    );
+    await sendAndConfirmTransaction(
+      await dao.depositToDao(3 * LAMPORTS_PER_SOL, daoMember2.publicKey),
+      connection,
+      [daoMember2]
+    );
+    await sendAndConfirmTransaction(
+      await dao.depositToDao(1 * LAMPORTS_PER_SOL, authority.publicKey),
+      connection,
+      [authority]
+    );
 
-      getLog(
-        `Authority ${authority.publicKey} invested ${
-          fr3.totalDepositAmount.toNumber() / LAMPORTS_PER_SOL
-        } SOL at timestamp: ${fr3.lastDepositAt.toNumber()}  and has ${(
-          (fr3.totalDepositAmount.toNumber() /
-            (treasurySolBalance * LAMPORTS_PER_SOL)) *
-          100
-        ).toFixed(2)} stake %`
+    //Treasury value doesn't change between deposits, so shares are minted 1:1
+    const deposits: [Keypair, number][] = [
+      [daoMember1, 2],
+      [daoMember2, 3],
+      [authority, 1],
+    ];
+    for (const [investor, amount] of deposits) {
+      const fr = await dao.getFinancialRecord(investor.publicKey);
+      expect(fr.totalDepositAmount.toNumber()).to.equal(
+        amount * LAMPORTS_PER_SOL
       );
-
-      getLog(`Treasury SOL balance after deposits: ${treasurySolBalance}`);
-    } catch (error) {
-      console.log(error);
+      expect(fr.shares.toNumber()).to.equal(amount * LAMPORTS_PER_SOL);
     }
 
+    expect(await dao.getTreasurySolBalance(connection)).to.equal(6);
+    expect((await dao.getDao()).totalShares.toNumber()).to.equal(
+      6 * LAMPORTS_PER_SOL
+    );
+
     const proposal1 = new Proposal(
       dao,
       program,
@@ -146,104 +117,77 @@ describe("aave_craft", () => {
       "Withdrawing potion of deposited sol",
       ProposalType.Withdrawal
     );
-    try {
-      getActionLog(`Creating withdrawal proposal`);
 
-      const ix = await proposal1.createWithdrawalProposal(3 * LAMPORTS_PER_SOL);
+    getActionLog(`Creating withdrawal proposal`);
+    await sendAndConfirmTransaction(
+      [await proposal1.createWithdrawalProposal(3 * LAMPORTS_PER_SOL)],
+      connection,
+      [authority]
+    );
 
-      await sendAndConfirmTransaction([ix], connection, [authority]);
-      const createdProposal = await proposal1.getProposal();
+    const createdProposal = await proposal1.getProposal();
+    expect(createdProposal.withdrawAmount.toNumber()).to.equal(
+      3 * LAMPORTS_PER_SOL
+    );
+    expect(createdProposal.proposalState).to.deep.equal({ voting: {} });
 
-      getLog(
-        `Created withdrawal proposal with name: ${
-          createdProposal.name
-        }, voting threshold: ${createdProposal.voteThreshold} votes and type: ${
-          Object.keys(createdProposal.proposalType)[0]
-        }`
-      );
-      getLog(
-        `Withdrawing ${
-          createdProposal.withdrawAmount.toNumber() / LAMPORTS_PER_SOL
-        } SOLs through proposal`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    getActionLog(`Casting votes`);
+    await sendAndConfirmTransaction(
+      [await proposal1.castVote(VoteOption.Yes, authority.publicKey)],
+      connection,
+      [authority]
+    );
 
-    try {
-      getActionLog(`Casting votes`);
-      const ix = await proposal1.castVote(VoteOption.Yes, authority.publicKey);
-      await sendAndConfirmTransaction([ix], connection, [authority]);
+    //Sixth of shares doesn't reach threshold
+    expect((await proposal1.getProposal()).proposalState).to.deep.equal({
+      voting: {},
+    });
 
-      const proposalData = await proposal1.getProposal();
-      getLog(
-        `Voted with authority, proposal has ${
-          proposalData.yesVotesCount
-        } and state ${Object.keys(proposalData.proposalState)[0]}`
-      );
-      const ix2 = await proposal1.castVote(
-        VoteOption.Yes,
-        daoMember2.publicKey
-      );
-      await sendAndConfirmTransaction([ix2], connection, [daoMember2]);
-      const updatedProposalData = await proposal1.getProposal();
-      getLog(
-        `Voted with authority, proposal has ${
-          updatedProposalData.yesVotesCount
-        } and state ${Object.keys(updatedProposalData.proposalState)[0]}`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    await sendAndConfirmTransaction(
+      [await proposal1.castVote(VoteOption.Yes, daoMember2.publicKey)],
+      connection,
+      [daoMember2]
+    );
 
-    try {
-      getActionLog(`Executing proposal`);
-      const ix = await proposal1.executeProposal(daoMember1.publicKey);
-      await sendAndConfirmTransaction([ix], connection, [daoMember1]);
-      const proposal = await proposal1.getProposal();
+    const votedProposal = await proposal1.getProposal();
+    expect(votedProposal.yesVotesCount.toNumber()).to.equal(
+      4 * LAMPORTS_PER_SOL
+    );
+    expect(votedProposal.proposalState).to.deep.equal({ succeded: {} });
 
-      getLog(
-        `Executed proposal, with final state ${
-          Object.keys(proposal.proposalState)[0]
-        }`
-      );
-      const treasurySolBalance = await dao.getTreasurySolBalance(connection);
-      getLog(
-        `Treasury balance after executing proposal ${treasurySolBalance} SOL`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    getActionLog(`Executing proposal`);
+    await sendAndConfirmTransaction(
+      [await proposal1.executeProposal(daoMember1.publicKey)],
+      connection,
+      [daoMember1]
+    );
 
-    try {
-      getActionLog(`Withdrawing funds`);
-      const authBalanceBefore = await getAccountSolBalance(
-        authority.publicKey,
-        connection
-      );
-      getLog(`Authority balance before withdrawing: ${authBalanceBefore}`);
-      const ix = await proposal1.withdrawMineFunds(authority.publicKey);
-      await sendAndConfirmTransaction([ix], connection, [authority]);
-      const authBalanceAfter = await getAccountSolBalance(
-        authority.publicKey,
-        connection
-      );
-      const fr1 = await dao.getFinancialRecord(authority.publicKey);
-      getLog(`Authority balance after withdrawing: ${authBalanceAfter}`);
-      const proposalData = await proposal1.getProposal();
-      const totalFinPower = await dao.getTotalFinancialPower();
-      getLog(
-        `Authority earned ${
-          authBalanceAfter - authBalanceBefore
-        } SOL because his ownership was ${(
-          fr1.totalDepositAmount.toNumber() / totalFinPower.toNumber()
-        ).toFixed(4)}%, out of withdrawable ${
-          proposalData.withdrawAmount.toNumber() / LAMPORTS_PER_SOL
-        }`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    expect((await proposal1.getProposal()).proposalState).to.deep.equal({
+      executed: {},
+    });
+    expect(await dao.getTreasurySolBalance(connection)).to.equal(3);
+
+    getActionLog(`Withdrawing funds`);
+    const authBalanceBefore = await connection.getBalance(authority.publicKey);
+
+    await sendAndConfirmTransaction(
+      [await proposal1.withdrawMineFunds(authority.publicKey)],
+      connection,
+      [authority]
+    );
+
+    //Authority holds sixth of shares, withdrawal record rent and fee are paid from payout
+    const authBalanceAfter = await connection.getBalance(authority.publicKey);
+    const [withdrawalData] = proposal1.getWithdrawalDataAddress();
+    const withdrawal = await program.account.withdrawalData.fetch(
+      withdrawalData
+    );
+    expect(withdrawal.totalWithdrawn.toNumber()).to.equal(
+      LAMPORTS_PER_SOL / 2
+    );
+    expect(authBalanceAfter - authBalanceBefore)
+      .to.be.greaterThan(0)
+      .and.lessThan(LAMPORTS_PER_SOL / 2);
 
     const proposal2 = new Proposal(
       dao,
@@ -255,93 +199,77 @@ describe("aave_craft", () => {
 
     const fundsReceivingAccount = await getKeypair(connection);
 
-    try {
-      getActionLog(`Creating investment proposal`);
-      const ix = await proposal2.createInvestingProposal(
-        2,
-        1.5 * LAMPORTS_PER_SOL,
-        fundsReceivingAccount.publicKey,
-        0.35 * LAMPORTS_PER_SOL,
-        1
-      );
-      await sendAndConfirmTransaction([ix], connection, [authority]);
-      const createdProposal = await proposal2.getProposal();
-      getLog(
-        `Created investment proposal with name: ${
-          createdProposal.name
-        }, and investing ${
-          createdProposal.vestingConfig.totalAmount.toNumber() /
-          LAMPORTS_PER_SOL
-        } SOL`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    getActionLog(`Creating investment proposal`);
+    await sendAndConfirmTransaction(
+      [
+        await proposal2.createInvestingProposal(
+          2,
+          1.5 * LAMPORTS_PER_SOL,
+          fundsReceivingAccount.publicKey,
+          0.35 * LAMPORTS_PER_SOL,
+          1
+        ),
+      ],
+      connection,
+      [authority]
+    );
 
-    try {
-      const ix1 = await proposal2.castVote(
-        VoteOption.Yes,
-        daoMember1.publicKey
-      );
-      const ix2 = await proposal2.castVote(
-        VoteOption.Yes,
-        daoMember2.publicKey
-      );
-      await sendAndConfirmTransaction([ix1], connection, [daoMember1]);
-      await sendAndConfirmTransaction([ix2], connection, [daoMember2]);
-      const votedProposal = await proposal2.getProposal();
-
-      getLog(
-        `Investment proposal is ${
-          Object.keys(votedProposal.proposalState)[0]
-        } after voting with total yes votes ${votedProposal.yesVotesCount}`
-      );
-    } catch (error) {
-      console.log(error);
-    }
+    expect(
+      (await proposal2.getProposal()).vestingConfig.totalAmount.toNumber()
+    ).to.equal(1.5 * LAMPORTS_PER_SOL);
 
-    try {
-      getActionLog(`Executing investment proposal`);
-      const treasurySolBalanceBefore = await dao.getTreasurySolBalance(
-        connection
-      );
+    await sendAndConfirmTransaction(
+      [await proposal2.castVote(VoteOption.Yes, daoMember1.publicKey)],
+      connection,
+      [daoMember1]
+    );
+    await sendAndConfirmTransaction(
+      [await proposal2.castVote(VoteOption.Yes, daoMember2.publicKey)],
+      connection,
+      [daoMember2]
+    );
 
-      getLog(`Treasury balance before investing: ${treasurySolBalanceBefore}`);
-      const ix = await proposal2.executeProposal(daoMember1.publicKey);
-      await sendAndConfirmTransaction([ix], connection, [daoMember1]);
+    expect((await proposal2.getProposal()).proposalState).to.deep.equal({
+      succeded: {},
+    });
 
-      const treasurySolBalanceAfter = await dao.getTreasurySolBalance(
-        connection
-      );
+    getActionLog(`Executing investment proposal`);
+    await sendAndConfirmTransaction(
+      [await proposal2.executeProposal(daoMember1.publicKey)],
+      connection,
+      [daoMember1]
+    );
 
-      getLog(`Treasury balance after investing: ${treasurySolBalanceAfter}`);
-    } catch (error) {
-      console.log(error);
-    }
+    expect(await dao.getTreasurySolBalance(connection)).to.equal(1.5);
+    expect((await dao.getDao()).investedAmount.toNumber()).to.equal(
+      1.5 * LAMPORTS_PER_SOL
+    );
 
-    try {
-      getActionLog(`Claiming vested tokens`);
-      getLog(`Awaiting 3 seconds (2 cliff time + 1 period time)`);
-      await new Promise((resolve) => setTimeout(resolve, 3000));
-      const balanceBefore = await getAccountSolBalance(
-        fundsReceivingAccount.publicKey,
-        connection
-      );
-      const ix = await proposal2.claimVestedTokens(
-        fundsReceivingAccount.publicKey
-      );
-      getLog(`SOL amount pre-claiming: ${balanceBefore}`);
-      await sendAndConfirmTransaction([ix], connection, [
-        fundsReceivingAccount,
-      ]);
-      const balanceAfter = await getAccountSolBalance(
-        fundsReceivingAccount.publicKey,
-        connection
-      );
+    getActionLog(`Claiming vested tokens`);
+    getLog(`Awaiting 4 seconds (2 cliff time + at least 1 period time)`);
+    await sleep(4);
 
-      getLog(`SOL amount post-claiming: ${balanceAfter}`);
-    } catch (error) {
-      console.log(error);
-    }
+    const balanceBefore = await connection.getBalance(
+      fundsReceivingAccount.publicKey
+    );
+
+    await sendAndConfirmTransaction(
+      [await proposal2.claimVestedTokens(fundsReceivingAccount.publicKey)],
+      connection,
+      [fundsReceivingAccount]
+    );
+
+    const balanceAfter = await connection.getBalance(
+      fundsReceivingAccount.publicKey
+    );
+    const vesting = await proposal2.getVesting();
+
+    expect(vesting.totalClaimed.toNumber()).to.be.at.least(
+      0.35 * LAMPORTS_PER_SOL
+    );
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(0);
+    expect(vesting.remainingAmount.toNumber()).to.equal(
+      1.5 * LAMPORTS_PER_SOL - vesting.totalClaimed.toNumber()
+    );
   });
 });
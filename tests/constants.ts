@@ -5,6 +5,11 @@ export const INVESTMENT_DAO_TREASURY_SEED = Buffer.from(
 export const DAO_PROPOSAL_SEED = Buffer.from("dao_proposal");
 export const WITHDRWAL_SEED = Buffer.from("withdrwal");
 export const VESTING_SEED = Buffer.from("vesting");
+export const DAO_GOVERNANCE_SEED = Buffer.from("dao_governance");
+export const DAO_SHARE_MINT_SEED = Buffer.from("dao_share_mint");
+export const TREASURY_ASSET_SEED = Buffer.from("treasury_asset");
+export const DAO_NAME_SEED = Buffer.from("dao_name");
+export const MILESTONE_VOTE_SEED = Buffer.from("milestone_vote");
 
 export enum DaoAction {
   Accept,
@@ -14,9 +19,21 @@ export enum DaoAction {
 export enum ProposalType {
   Investing,
   Withdrawal,
+  MultiChoiceInvesting,
+  UpdateGovernance,
+  InviteInvestor,
+  TransferAuthority,
+  RemoveMember,
+  UpdateAllowlist,
+  RevokeVesting,
+  ApproveMilestone,
+  RegisterTreasuryAsset,
+  RevokeInvitation,
+  SetTreasuryAssetPrice,
 }
 
 export enum VoteOption {
   Yes,
   No,
+  Abstain,
 }
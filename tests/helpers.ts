@@ -1,3 +1,4 @@
+import { keccak_256 } from "@noble/hashes/sha3";
 import {
   Connection,
   Keypair,
@@ -7,6 +8,8 @@ import {
   TransactionMessage,
   VersionedTransaction,
 } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
 
 export async function sendAndConfirmTransaction(
   instructions: TransactionInstruction[],
@@ -69,3 +72,39 @@ export async function getAccountSolBalance(
 
   return balance / LAMPORTS_PER_SOL;
 }
+
+//Program errors are reported in simulation logs, so failed transaction is matched by error name
+export async function expectError(promise: Promise<unknown>, errorName: string) {
+  try {
+    await promise;
+  } catch (error) {
+    const logs: string[] = error.logs ?? [];
+
+    expect(`${error} ${logs.join(" ")}`).to.contain(errorName);
+    return;
+  }
+
+  expect.fail(`Transaction didn't fail with ${errorName}`);
+}
+
+export function sleep(seconds: number) {
+  return new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+}
+
+//Same hashing as allowlist merkle tree of program
+export function getAllowlistLeaf(wallet: PublicKey, allocation: number) {
+  return keccak_256(
+    Buffer.concat([
+      Buffer.from([0]),
+      wallet.toBuffer(),
+      new BN(allocation).toArrayLike(Buffer, "le", 8),
+    ])
+  );
+}
+
+export function hashAllowlistPair(a: Uint8Array, b: Uint8Array) {
+  const [first, second] =
+    Buffer.compare(Buffer.from(a), Buffer.from(b)) <= 0 ? [a, b] : [b, a];
+
+  return keccak_256(Buffer.concat([Buffer.from([1]), first, second]));
+}
@@ -0,0 +1,311 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { createAccount, getAccount } from "@solana/spl-token";
+import { Connection, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { AaveCraft } from "../target/types/aave_craft";
+import { ProposalType, VoteOption } from "./constants";
+import {
+  expectError,
+  getAllowlistLeaf,
+  getKeypair,
+  hashAllowlistPair,
+  sendAndConfirmTransaction,
+} from "./helpers";
+import { Dao, DaoOptions } from "./models/dao";
+import { Proposal } from "./models/proposal";
+
+describe("membership", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.AaveCraft as Program<AaveCraft>;
+
+  const connection = new Connection("http://localhost:8899", "confirmed");
+
+  async function createDao(options: DaoOptions = {}) {
+    const authority = await getKeypair(connection);
+    const dao = new Dao(authority, program, 20, 51, options);
+
+    await sendAndConfirmTransaction([await dao.createDao()], connection, [
+      authority,
+    ]);
+
+    return dao;
+  }
+
+  async function join(dao: Dao, member: Keypair) {
+    await sendAndConfirmTransaction(
+      [await dao.joinDao(member.publicKey)],
+      connection,
+      [member]
+    );
+  }
+
+  async function deposit(dao: Dao, member: Keypair, amount: number) {
+    await sendAndConfirmTransaction(
+      await dao.depositToDao(amount, member.publicKey),
+      connection,
+      [member]
+    );
+  }
+
+  it("joins open DAO, but not invite only DAO", async () => {
+    const dao = await createDao({ membershipPolicy: { open: {} } });
+    const member = await getKeypair(connection);
+
+    await join(dao, member);
+
+    const investorData = await dao.getInvestorData(member.publicKey);
+    expect(investorData.state).to.deep.equal({ accepted: {} });
+    expect((await dao.getDao()).investorsCount).to.equal(1);
+
+    const inviteOnlyDao = await createDao();
+
+    await expectError(join(inviteOnlyDao, member), "JoinNotAllowed");
+  });
+
+  it("joins allowlisted DAO only with proof of own allocation", async () => {
+    const member = await getKeypair(connection);
+    const allocation = 5 * LAMPORTS_PER_SOL;
+
+    const leaf = getAllowlistLeaf(member.publicKey, allocation);
+    const siblingLeaf = getAllowlistLeaf(Keypair.generate().publicKey, 1);
+    const root = hashAllowlistPair(leaf, siblingLeaf);
+
+    const dao = await createDao({ allowlistRoot: Array.from(root) });
+    const proof = [Array.from(siblingLeaf)];
+
+    await expectError(
+      sendAndConfirmTransaction(
+        [await dao.joinDaoWithProof(member.publicKey, allocation + 1, proof)],
+        connection,
+        [member]
+      ),
+      "InvalidAllowlistProof"
+    );
+
+    await sendAndConfirmTransaction(
+      [await dao.joinDaoWithProof(member.publicKey, allocation, proof)],
+      connection,
+      [member]
+    );
+
+    const investorData = await dao.getInvestorData(member.publicKey);
+    expect(investorData.state).to.deep.equal({ accepted: {} });
+    expect(investorData.allocation.toNumber()).to.equal(allocation);
+  });
+
+  it("ragequits with pro-rata payout once supporting votes are gone", async () => {
+    const dao = await createDao({ membershipPolicy: { open: {} } });
+    const member = await getKeypair(connection);
+
+    await join(dao, member);
+    await deposit(dao, member, 1 * LAMPORTS_PER_SOL);
+    await deposit(dao, dao.authority, 3 * LAMPORTS_PER_SOL);
+
+    const proposal = new Proposal(
+      dao,
+      program,
+      "Withdrawal",
+      "Withdrawal that stays in voting",
+      ProposalType.Withdrawal
+    );
+    await sendAndConfirmTransaction(
+      [await proposal.createWithdrawalProposal(LAMPORTS_PER_SOL)],
+      connection,
+      [dao.authority]
+    );
+
+    //Member holds 25% of shares, so yes vote doesn't decide proposal
+    await sendAndConfirmTransaction(
+      [await proposal.castVote(VoteOption.Yes, member.publicKey)],
+      connection,
+      [member]
+    );
+    expect(
+      (await dao.getInvestorData(member.publicKey)).supportingVotesCount
+    ).to.equal(1);
+
+    await expectError(
+      sendAndConfirmTransaction(
+        [await dao.ragequit(member.publicKey)],
+        connection,
+        [member]
+      ),
+      "MemberHasSupportingVotes"
+    );
+
+    await sendAndConfirmTransaction(
+      [await proposal.changeVote(VoteOption.No, member.publicKey)],
+      connection,
+      [member]
+    );
+    expect(
+      (await dao.getInvestorData(member.publicKey)).supportingVotesCount
+    ).to.equal(0);
+
+    //Only investor ATA is accepted as share account
+    const otherShareAccount = await createAccount(
+      connection,
+      member,
+      dao.getShareMintAddress(),
+      member.publicKey,
+      Keypair.generate()
+    );
+    await expectError(
+      sendAndConfirmTransaction(
+        [await dao.ragequit(member.publicKey, [], otherShareAccount)],
+        connection,
+        [member]
+      ),
+      "ConstraintAssociated"
+    );
+
+    const treasuryBefore = await connection.getBalance(
+      dao.getDaoTreasuryAddress()
+    );
+
+    await sendAndConfirmTransaction(
+      [await dao.ragequit(member.publicKey)],
+      connection,
+      [member]
+    );
+
+    const treasuryAfter = await connection.getBalance(
+      dao.getDaoTreasuryAddress()
+    );
+    expect(treasuryBefore - treasuryAfter).to.equal(
+      Math.floor(treasuryBefore / 4)
+    );
+
+    const daoData = await dao.getDao();
+    expect(daoData.totalShares.toNumber()).to.equal(3 * LAMPORTS_PER_SOL);
+    expect(daoData.investorsCount).to.equal(0);
+    expect(await dao.getInvestorData(member.publicKey)).to.equal(null);
+
+    const shareAccount = await getAccount(
+      connection,
+      dao.getShareAccountAddress(member.publicKey)
+    );
+    expect(Number(shareAccount.amount)).to.equal(0);
+
+    //No vote of member that left can still be relinquished, without investor data
+    await sendAndConfirmTransaction(
+      [await proposal.relinquishVote(member.publicKey, false)],
+      connection,
+      [member]
+    );
+    expect((await proposal.getProposal()).noVotesCount.toNumber()).to.equal(0);
+  });
+
+  it("doesn't uncount other member when DAO creator ragequits", async () => {
+    const dao = await createDao({ membershipPolicy: { open: {} } });
+    const member = await getKeypair(connection);
+
+    await join(dao, member);
+    await deposit(dao, dao.authority, LAMPORTS_PER_SOL);
+
+    await sendAndConfirmTransaction(
+      [await dao.ragequit(dao.authority.publicKey)],
+      connection,
+      [dao.authority]
+    );
+
+    expect((await dao.getDao()).investorsCount).to.equal(1);
+  });
+
+  it("removes member for good", async () => {
+    const dao = await createDao({ membershipPolicy: { open: {} } });
+    const member = await getKeypair(connection);
+
+    await join(dao, member);
+    await deposit(dao, member, LAMPORTS_PER_SOL);
+
+    const balanceBefore = await connection.getBalance(member.publicKey);
+
+    await sendAndConfirmTransaction(
+      [await dao.removeMember(member.publicKey)],
+      connection,
+      [dao.authority]
+    );
+
+    //Whole treasury belongs to removed member
+    expect(
+      (await connection.getBalance(member.publicKey)) - balanceBefore
+    ).to.be.at.least(LAMPORTS_PER_SOL);
+    expect((await dao.getDao()).totalShares.toNumber()).to.equal(0);
+
+    const investorData = await dao.getInvestorData(member.publicKey);
+    expect(investorData.state).to.deep.equal({ removed: {} });
+
+    await expectError(join(dao, member), "already in use");
+  });
+
+  it("transfers ownership only between member ATAs", async () => {
+    const dao = await createDao({ membershipPolicy: { open: {} } });
+    const sender = await getKeypair(connection);
+    const recipient = await getKeypair(connection);
+    const outsider = await getKeypair(connection);
+
+    await join(dao, sender);
+    await join(dao, recipient);
+    await deposit(dao, sender, 2 * LAMPORTS_PER_SOL);
+
+    await expectError(
+      sendAndConfirmTransaction(
+        await dao.transferOwnership(
+          sender.publicKey,
+          outsider.publicKey,
+          LAMPORTS_PER_SOL
+        ),
+        connection,
+        [outsider, sender]
+      ),
+      "AccountNotInitialized"
+    );
+
+    const otherShareAccount = await createAccount(
+      connection,
+      recipient,
+      dao.getShareMintAddress(),
+      recipient.publicKey,
+      Keypair.generate()
+    );
+    await expectError(
+      sendAndConfirmTransaction(
+        await dao.transferOwnership(
+          sender.publicKey,
+          recipient.publicKey,
+          LAMPORTS_PER_SOL,
+          otherShareAccount
+        ),
+        connection,
+        [recipient, sender]
+      ),
+      "ConstraintAssociated"
+    );
+
+    await sendAndConfirmTransaction(
+      await dao.transferOwnership(
+        sender.publicKey,
+        recipient.publicKey,
+        LAMPORTS_PER_SOL / 2
+      ),
+      connection,
+      [recipient, sender]
+    );
+
+    const senderRecord = await dao.getFinancialRecord(sender.publicKey);
+    const recipientRecord = await dao.getFinancialRecord(recipient.publicKey);
+    expect(senderRecord.shares.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+    expect(recipientRecord.shares.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+
+    const recipientShareAccount = await getAccount(
+      connection,
+      dao.getShareAccountAddress(recipient.publicKey)
+    );
+    expect(Number(recipientShareAccount.amount)).to.equal(
+      LAMPORTS_PER_SOL / 2
+    );
+  });
+});
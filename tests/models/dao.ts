@@ -1,6 +1,11 @@
 import { Program } from "@coral-xyz/anchor";
 import { TOKEN_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  AccountMeta,
   Connection,
   Keypair,
   LAMPORTS_PER_SOL,
@@ -12,47 +17,81 @@ import { BN } from "bn.js";
 import { v4 } from "uuid";
 import { AaveCraft } from "../../target/types/aave_craft";
 import {
+  DAO_SHARE_MINT_SEED,
   DaoAction,
   INVESTMENT_DAO_SEED,
   INVESTMENT_DAO_TREASURY_SEED,
+  TREASURY_ASSET_SEED,
 } from "../constants";
 
+export type DaoOptions = {
+  vetoWindow?: number;
+  //Anchor enum argument, e.g. { open: {} }
+  membershipPolicy?: object;
+  allowlistRoot?: number[] | null;
+};
+
 export class Dao {
   name: string;
+  nonce: BN;
   authority: Keypair;
   program: Program<AaveCraft>;
   maxVotingTime: number;
   votingQuorum: number;
   daoMembers: PublicKey[];
+  options: DaoOptions;
   constructor(
     authority: Keypair,
     program: Program<AaveCraft>,
     maxVotingTime: number,
-    votingQuorum: number
+    votingQuorum: number,
+    options: DaoOptions = {}
   ) {
     this.name = `DAO:` + v4().slice(0, 8);
+    this.nonce = new BN(Date.now());
     this.authority = authority;
     this.program = program;
     this.maxVotingTime = maxVotingTime;
     this.votingQuorum = votingQuorum;
     this.daoMembers = [authority.publicKey];
+    this.options = options;
   }
 
   async createDao() {
     const daoAddress = this.getDaoPda();
     const ix = await this.program.methods
-      .createInvestmentDao(this.name, {
-        maxVotingTime: new BN(this.maxVotingTime),
-        votingQuorum: this.votingQuorum,
-      })
+      .createInvestmentDao(
+        this.nonce,
+        this.name,
+        {
+          maxVotingTime: new BN(this.maxVotingTime),
+          minVotingTime: new BN(0),
+          votingQuorum: this.votingQuorum,
+          approvalThreshold: this.votingQuorum,
+          withdrawalApprovalThreshold: this.votingQuorum,
+          earlyTipping: true,
+          vetoWindow: new BN(this.options.vetoWindow ?? 0),
+        },
+        {
+          hardCap: null,
+          minTicket: new BN(0),
+          maxTicket: null,
+          opensAt: null,
+          closesAt: null,
+        },
+        this.options.allowlistRoot ?? null,
+        this.options.membershipPolicy ?? { inviteOnly: {} }
+      )
       .accounts({
         investmentDao: daoAddress,
         daoAuthority: this.authority.publicKey,
         denominatedCurrency: SystemProgram.programId,
         investorData: this.getInvestorDataAddress(this.authority.publicKey),
+        shareMint: this.getShareMintAddress(),
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
       })
       .instruction();
 
@@ -61,16 +100,40 @@ export class Dao {
 
   getDaoPda() {
     const [daoAddress] = PublicKey.findProgramAddressSync(
-      [INVESTMENT_DAO_SEED, Buffer.from(this.name)],
+      [
+        INVESTMENT_DAO_SEED,
+        this.authority.publicKey.toBuffer(),
+        this.nonce.toArrayLike(Buffer, "le", 8),
+      ],
       this.program.programId
     );
 
     return daoAddress;
   }
 
-  async inviteDaoMember(memberAddress: PublicKey) {
+  getShareMintAddress() {
+    const [shareMint] = PublicKey.findProgramAddressSync(
+      [DAO_SHARE_MINT_SEED, this.getDaoPda().toBuffer()],
+      this.program.programId
+    );
+
+    return shareMint;
+  }
+
+  getShareAccountAddress(wallet: PublicKey) {
+    return getAssociatedTokenAddressSync(this.getShareMintAddress(), wallet);
+  }
+
+  async inviteDaoMember(
+    memberAddress: PublicKey,
+    allocation: number | null = null,
+    expiresAt: number | null = null
+  ) {
     const ix = await this.program.methods
-      .inviteDaoInvestor()
+      .inviteDaoInvestor(
+        allocation === null ? null : new BN(allocation),
+        expiresAt === null ? null : new BN(expiresAt)
+      )
       .accounts({
         authority: this.authority.publicKey,
         daoInvestor: this.getInvestorDataAddress(memberAddress),
@@ -109,8 +172,9 @@ export class Dao {
       .accounts({
         investor: invitedMember.publicKey,
         investmentDao: this.getDaoPda(),
-        authority: this.authority.publicKey,
         investorData: this.getInvestorDataAddress(invitedMember.publicKey),
+        //Members in tests are invited by DAO authority
+        inviter: this.authority.publicKey,
       })
       .instruction();
 
@@ -121,12 +185,12 @@ export class Dao {
     this.daoMembers.push(member);
   }
 
-  getDaoTreasuryAddress() {
+  getDaoTreasuryAddress(currency: PublicKey = SystemProgram.programId) {
     const [treasury] = PublicKey.findProgramAddressSync(
       [
         INVESTMENT_DAO_TREASURY_SEED,
         this.getDaoPda().toBuffer(),
-        SystemProgram.programId.toBuffer(),
+        currency.toBuffer(),
       ],
       this.program.programId
     );
@@ -134,9 +198,25 @@ export class Dao {
     return treasury;
   }
 
-  async depositToDao(amount: number, investor: PublicKey) {
+  //Registered treasury assets are passed as (treasury asset, asset treasury) pairs, so deposit can be priced
+  async depositToDao(
+    amount: number,
+    investor: PublicKey,
+    treasuryAssets: PublicKey[] = []
+  ) {
     const dao = this.getDaoPda();
     const investorDataAddress = this.getInvestorDataAddress(investor);
+    const shareMint = this.getShareMintAddress();
+    const investorShareAccount = this.getShareAccountAddress(investor);
+
+    const createShareAccountIx =
+      createAssociatedTokenAccountIdempotentInstruction(
+        investor,
+        investorShareAccount,
+        investor,
+        shareMint
+      );
+
     const ix = await this.program.methods
       .depositFunds(new BN(amount))
       .accounts({
@@ -147,11 +227,28 @@ export class Dao {
         systemProgram: SystemProgram.programId,
         investorFinancialRecord:
           this.getInvestorFinancialRecord(investorDataAddress),
+        shareMint,
+        investorShareAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(
+        treasuryAssets.flatMap((mint) => [
+          {
+            isSigner: false,
+            isWritable: false,
+            pubkey: this.getTreasuryAssetAddress(mint),
+          },
+          {
+            isSigner: false,
+            isWritable: false,
+            pubkey: this.getDaoTreasuryAddress(mint),
+          },
+        ])
+      )
       .instruction();
 
-    return ix;
+    return [createShareAccountIx, ix];
   }
 
   async getFinancialRecord(wallet: PublicKey) {
@@ -179,4 +276,211 @@ export class Dao {
 
     return acc.totalDeposited;
   }
+
+  async getDao() {
+    return await this.program.account.investmentDao.fetch(this.getDaoPda());
+  }
+
+  async getInvestorData(wallet: PublicKey) {
+    return await this.program.account.investorData.fetchNullable(
+      this.getInvestorDataAddress(wallet)
+    );
+  }
+
+  async joinDao(investor: PublicKey) {
+    const ix = await this.program.methods
+      .joinDao()
+      .accounts({
+        investor,
+        investmentDao: this.getDaoPda(),
+        investorData: this.getInvestorDataAddress(investor),
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async joinDaoWithProof(
+    investor: PublicKey,
+    allocation: number,
+    proof: number[][]
+  ) {
+    const ix = await this.program.methods
+      .joinDaoWithProof(new BN(allocation), proof)
+      .accounts({
+        investor,
+        investmentDao: this.getDaoPda(),
+        investorData: this.getInvestorDataAddress(investor),
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  //Pro-rata share of each registered asset is paid to investor ATA of asset mint
+  getExitTreasuryAssetAccounts(
+    investor: PublicKey,
+    treasuryAssets: PublicKey[]
+  ): AccountMeta[] {
+    return treasuryAssets.flatMap((mint) => [
+      {
+        isSigner: false,
+        isWritable: true,
+        pubkey: this.getTreasuryAssetAddress(mint),
+      },
+      {
+        isSigner: false,
+        isWritable: true,
+        pubkey: this.getDaoTreasuryAddress(mint),
+      },
+      {
+        isSigner: false,
+        isWritable: true,
+        pubkey: getAssociatedTokenAddressSync(mint, investor),
+      },
+      { isSigner: false, isWritable: false, pubkey: mint },
+      { isSigner: false, isWritable: false, pubkey: TOKEN_PROGRAM_ID },
+    ]);
+  }
+
+  async ragequit(
+    investor: PublicKey,
+    treasuryAssets: PublicKey[] = [],
+    shareAccount: PublicKey = this.getShareAccountAddress(investor)
+  ) {
+    const investorData = this.getInvestorDataAddress(investor);
+
+    const ix = await this.program.methods
+      .ragequit()
+      .accounts({
+        investor,
+        investmentDao: this.getDaoPda(),
+        daoTreasury: this.getDaoTreasuryAddress(),
+        investorData,
+        investorFinancialRecord: this.getInvestorFinancialRecord(investorData),
+        shareMint: this.getShareMintAddress(),
+        investorShareAccount: shareAccount,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        this.getExitTreasuryAssetAccounts(investor, treasuryAssets)
+      )
+      .instruction();
+
+    return ix;
+  }
+
+  async removeMember(investor: PublicKey, treasuryAssets: PublicKey[] = []) {
+    const investorData = this.getInvestorDataAddress(investor);
+
+    const ix = await this.program.methods
+      .removeMember()
+      .accounts({
+        authority: this.authority.publicKey,
+        investmentDao: this.getDaoPda(),
+        daoTreasury: this.getDaoTreasuryAddress(),
+        investor,
+        investorData,
+        investorFinancialRecord: this.getInvestorFinancialRecord(investorData),
+        shareMint: this.getShareMintAddress(),
+        investorShareAccount: this.getShareAccountAddress(investor),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        this.getExitTreasuryAssetAccounts(investor, treasuryAssets)
+      )
+      .instruction();
+
+    return ix;
+  }
+
+  async transferOwnership(
+    sender: PublicKey,
+    recipient: PublicKey,
+    shares: number,
+    recipientShareAccount: PublicKey = this.getShareAccountAddress(recipient)
+  ) {
+    const senderData = this.getInvestorDataAddress(sender);
+    const recipientData = this.getInvestorDataAddress(recipient);
+    const shareMint = this.getShareMintAddress();
+
+    const createShareAccountIx =
+      createAssociatedTokenAccountIdempotentInstruction(
+        recipient,
+        this.getShareAccountAddress(recipient),
+        recipient,
+        shareMint
+      );
+
+    const ix = await this.program.methods
+      .transferOwnership(new BN(shares))
+      .accounts({
+        sender,
+        recipient,
+        investmentDao: this.getDaoPda(),
+        senderData,
+        senderFinancialRecord: this.getInvestorFinancialRecord(senderData),
+        recipientData,
+        recipientFinancialRecord: this.getInvestorFinancialRecord(recipientData),
+        shareMint,
+        senderShareAccount: this.getShareAccountAddress(sender),
+        recipientShareAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    return [createShareAccountIx, ix];
+  }
+
+  getTreasuryAssetAddress(mint: PublicKey) {
+    const [treasuryAsset] = PublicKey.findProgramAddressSync(
+      [TREASURY_ASSET_SEED, this.getDaoPda().toBuffer(), mint.toBuffer()],
+      this.program.programId
+    );
+
+    return treasuryAsset;
+  }
+
+  async registerTreasuryAsset(mint: PublicKey) {
+    const ix = await this.program.methods
+      .registerTreasuryAsset()
+      .accounts({
+        authority: this.authority.publicKey,
+        payer: this.authority.publicKey,
+        investmentDao: this.getDaoPda(),
+        mint,
+        treasuryAsset: this.getTreasuryAssetAddress(mint),
+        assetTreasury: this.getDaoTreasuryAddress(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async syncTreasuryAsset(mint: PublicKey) {
+    const ix = await this.program.methods
+      .syncTreasuryAsset()
+      .accounts({
+        treasuryAsset: this.getTreasuryAssetAddress(mint),
+        assetTreasury: this.getDaoTreasuryAddress(mint),
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async getTreasuryAsset(mint: PublicKey) {
+    return await this.program.account.treasuryAsset.fetch(
+      this.getTreasuryAssetAddress(mint)
+    );
+  }
 }
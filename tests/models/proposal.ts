@@ -1,16 +1,13 @@
 import { Program } from "@coral-xyz/anchor";
 import { TOKEN_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
-import {
-  AccountMeta,
-  PublicKey,
-  SystemProgram,
-  SYSVAR_RENT_PUBKEY,
-} from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { AccountMeta, PublicKey, SystemProgram } from "@solana/web3.js";
 import { BN } from "bn.js";
 import { AaveCraft } from "../../target/types/aave_craft";
 import {
   DAO_PROPOSAL_SEED,
   INVESTMENT_DAO_SEED,
+  MILESTONE_VOTE_SEED,
   ProposalType,
   VESTING_SEED,
   VoteOption,
@@ -18,6 +15,17 @@ import {
 } from "../constants";
 import { Dao } from "./dao";
 
+function getVoteOptionArg(voteOption: VoteOption) {
+  switch (voteOption) {
+    case VoteOption.No:
+      return { no: {} };
+    case VoteOption.Abstain:
+      return { abstain: {} };
+    default:
+      return { yes: {} };
+  }
+}
+
 export class Proposal {
   program: Program<AaveCraft>;
   dao: Dao;
@@ -26,6 +34,10 @@ export class Proposal {
   proposalAddress: PublicKey;
   proposalType: ProposalType;
   proposalIndex: number;
+  //Treasury asset of withdrawal proposal
+  asset: PublicKey = SystemProgram.programId;
+  //Vesting targeted by revoke vesting proposal
+  targetVesting: PublicKey;
   constructor(
     dao: Dao,
     program: Program<AaveCraft>,
@@ -41,16 +53,21 @@ export class Proposal {
     this.proposalType = proposalType;
   }
 
-  async createWithdrawalProposal(withdrawAmount: number) {
+  async createWithdrawalProposal(
+    withdrawAmount: number,
+    asset: PublicKey = SystemProgram.programId
+  ) {
     const { proposalAddress, proposalIndex } = await this.getNewProposalPda();
     this.proposalAddress = proposalAddress;
     this.proposalIndex = proposalIndex;
+    this.asset = asset;
     const ix = await this.program.methods
       .createProposal(
-        { withdrawal: {} },
+        { withdrawal: { asset } },
         this.name,
         this.description,
         new BN(withdrawAmount),
+        null,
         null
       )
       .accounts({
@@ -62,6 +79,13 @@ export class Proposal {
         systemProgram: SystemProgram.programId,
         proposal: this.proposalAddress,
       })
+      .remainingAccounts([
+        {
+          isSigner: false,
+          isWritable: false,
+          pubkey: this.dao.getDaoTreasuryAddress(asset),
+        },
+      ])
       .instruction();
 
     return ix;
@@ -109,13 +133,24 @@ export class Proposal {
     this.proposalAddress = proposalAddress;
     this.proposalIndex = proposalIndex;
     const ix = await this.program.methods
-      .createProposal({ investing: {} }, this.name, this.description, null, {
-        cliff: new BN(cliffAmount),
-        authority: receiver,
-        totalAmount: new BN(totalAmount),
-        amountPerPeriod: new BN(amountPerPeriod),
-        period: new BN(period),
-      })
+      .createProposal(
+        { investing: {} },
+        this.name,
+        this.description,
+        null,
+        {
+          totalAmount: new BN(totalAmount),
+          authority: receiver,
+          schedule: {
+            step: {
+              cliff: new BN(cliffAmount),
+              period: new BN(period),
+              amountPerPeriod: new BN(amountPerPeriod),
+            },
+          },
+        },
+        null
+      )
       .accounts({
         authority: this.dao.authority.publicKey,
         investmentDao: this.dao.getDaoPda(),
@@ -125,6 +160,13 @@ export class Proposal {
         systemProgram: SystemProgram.programId,
         proposal: proposalAddress,
       })
+      .remainingAccounts([
+        {
+          isSigner: false,
+          isWritable: false,
+          pubkey: this.dao.getDaoTreasuryAddress(),
+        },
+      ])
       .instruction();
 
     return ix;
@@ -141,9 +183,9 @@ export class Proposal {
     return withdrawalRecord;
   }
 
-  async withdrawMineFunds(authority: PublicKey) {
-    const daoAddress = this.dao.getDaoPda();
-    const investorData = this.dao.getInvestorDataAddress(authority);
+  async withdrawMineFunds(authority: PublicKey, memberDao: Dao = this.dao) {
+    const daoAddress = memberDao.getDaoPda();
+    const investorData = memberDao.getInvestorDataAddress(authority);
 
     const [withdrawalData, withdrawalTreasury] =
       this.getWithdrawalDataAddress();
@@ -159,10 +201,22 @@ export class Proposal {
         withdrawalTreasury,
         withdrawalRecord: this.getWithdrawalRecordAddress(authority),
         investorFinancialRecord:
-          this.dao.getInvestorFinancialRecord(investorData),
+          memberDao.getInvestorFinancialRecord(investorData),
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(
+        this.asset.equals(SystemProgram.programId)
+          ? []
+          : [
+              {
+                isSigner: false,
+                isWritable: true,
+                pubkey: getAssociatedTokenAddressSync(this.asset, authority),
+              },
+              { isSigner: false, isWritable: false, pubkey: this.asset },
+            ]
+      )
       .instruction();
 
     return ix;
@@ -181,12 +235,17 @@ export class Proposal {
     return voteRecordAddress;
   }
 
-  async castVote(voteOption: VoteOption, authority: PublicKey) {
-    const dao = this.dao.getDaoPda();
-    const investorData = this.dao.getInvestorDataAddress(authority);
-    const investorFr = this.dao.getInvestorFinancialRecord(investorData);
+  //Member of other DAO can be passed to check that votes are bound to DAO of proposal
+  async castVote(
+    voteOption: VoteOption,
+    authority: PublicKey,
+    memberDao: Dao = this.dao
+  ) {
+    const dao = memberDao.getDaoPda();
+    const investorData = memberDao.getInvestorDataAddress(authority);
+    const investorFr = memberDao.getInvestorFinancialRecord(investorData);
     const ix = await this.program.methods
-      .castVote(voteOption === VoteOption.No ? { no: {} } : { yes: {} })
+      .castVote(getVoteOptionArg(voteOption))
       .accounts({
         investmentDao: dao,
         investor: authority,
@@ -217,11 +276,33 @@ export class Proposal {
 
   async executeProposal(authority: PublicKey) {
     const dao = this.dao.getDaoPda();
-    const daoTreasury = this.dao.getDaoTreasuryAddress();
+    const daoTreasury = this.dao.getDaoTreasuryAddress(
+      this.proposalType === ProposalType.Withdrawal
+        ? this.asset
+        : SystemProgram.programId
+    );
 
     const remainingAccounts: AccountMeta[] = [];
 
-    if (this.proposalType === ProposalType.Withdrawal) {
+    if (this.proposalType === ProposalType.RevokeVesting) {
+      const [vestingTreasury] = PublicKey.findProgramAddressSync(
+        [VESTING_SEED, this.targetVesting.toBuffer()],
+        this.program.programId
+      );
+
+      remainingAccounts.push(
+        {
+          isSigner: false,
+          isWritable: true,
+          pubkey: this.targetVesting,
+        },
+        {
+          isSigner: false,
+          isWritable: true,
+          pubkey: vestingTreasury,
+        }
+      );
+    } else if (this.proposalType === ProposalType.Withdrawal) {
       const [withdrawalData, withdrawalTreasury] =
         this.getWithdrawalDataAddress();
 
@@ -237,6 +318,18 @@ export class Proposal {
           pubkey: withdrawalTreasury,
         }
       );
+
+      //Token withdrawals pass mint, and treasury asset when asset isn't denominated currency
+      if (!this.asset.equals(SystemProgram.programId)) {
+        remainingAccounts.push(
+          { isSigner: false, isWritable: false, pubkey: this.asset },
+          {
+            isSigner: false,
+            isWritable: true,
+            pubkey: this.dao.getTreasuryAssetAddress(this.asset),
+          }
+        );
+      }
     } else {
       const [vestingData, vestingTreasury] = this.getVestingDataAddresses();
       remainingAccounts.push(
@@ -258,11 +351,11 @@ export class Proposal {
       .accounts({
         payer: authority,
         proposal: this.proposalAddress,
-        rent: SYSVAR_RENT_PUBKEY,
         daoTreasury,
         investmentDao: dao,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        currencyTokenProgram: TOKEN_PROGRAM_ID,
       })
       .remainingAccounts(remainingAccounts)
       .instruction();
@@ -307,4 +400,238 @@ export class Proposal {
 
     return [vesting, vestingTreasury];
   }
+
+  async changeVote(voteOption: VoteOption, authority: PublicKey) {
+    const ix = await this.program.methods
+      .changeVote(getVoteOptionArg(voteOption))
+      .accounts({
+        investor: authority,
+        investmentDao: this.dao.getDaoPda(),
+        investorData: this.dao.getInvestorDataAddress(authority),
+        proposal: this.proposalAddress,
+        voteRecord: this.getVoteRecordAddress(authority),
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  //Investor data is needed only for yes votes, member that left DAO passes none
+  async relinquishVote(authority: PublicKey, withInvestorData = true) {
+    const ix = await this.program.methods
+      .relinquishVote()
+      .accounts({
+        investor: authority,
+        investmentDao: this.dao.getDaoPda(),
+        investorData: withInvestorData
+          ? this.dao.getInvestorDataAddress(authority)
+          : null,
+        proposal: this.proposalAddress,
+        voteRecord: this.getVoteRecordAddress(authority),
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  //Cancel by proposal creator while voting, or veto by DAO authority once proposal succeded
+  async cancelProposal(authority: PublicKey) {
+    const proposal = await this.getProposal();
+
+    const ix = await this.program.methods
+      .cancelProposal()
+      .accounts({
+        authority,
+        investmentDao: this.dao.getDaoPda(),
+        proposal: this.proposalAddress,
+        proposalAuthority: proposal.authority,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async createRevokeVestingProposal(vesting: PublicKey) {
+    this.targetVesting = vesting;
+
+    return await this.createPayloadProposal({ revokeVesting: { vesting } }, [
+      vesting,
+    ]);
+  }
+
+  //Investment whose tranches are unlocked by member vote milestones
+  async createMilestoneInvestingProposal(
+    receiver: PublicKey,
+    milestoneAmounts: number[]
+  ) {
+    const { proposalAddress, proposalIndex } = await this.getNewProposalPda();
+    this.proposalAddress = proposalAddress;
+    this.proposalIndex = proposalIndex;
+    const ix = await this.program.methods
+      .createProposal(
+        { investing: {} },
+        this.name,
+        this.description,
+        null,
+        {
+          totalAmount: new BN(
+            milestoneAmounts.reduce((sum, amount) => sum + amount, 0)
+          ),
+          authority: receiver,
+          schedule: {
+            milestones: {
+              milestones: milestoneAmounts.map((amount, index) => ({
+                name: `Milestone ${index}`,
+                amount: new BN(amount),
+                approval: { memberVote: {} },
+                approvedAt: null,
+              })),
+            },
+          },
+        },
+        null
+      )
+      .accounts({
+        authority: this.dao.authority.publicKey,
+        investmentDao: this.dao.getDaoPda(),
+        investorData: this.dao.getInvestorDataAddress(
+          this.dao.authority.publicKey
+        ),
+        systemProgram: SystemProgram.programId,
+        proposal: proposalAddress,
+      })
+      .remainingAccounts([
+        {
+          isSigner: false,
+          isWritable: false,
+          pubkey: this.dao.getDaoTreasuryAddress(),
+        },
+      ])
+      .instruction();
+
+    return ix;
+  }
+
+  getMilestoneVoteAddress(milestoneIndex: number) {
+    const [vesting] = this.getVestingData();
+    const [milestoneVote] = PublicKey.findProgramAddressSync(
+      [MILESTONE_VOTE_SEED, vesting.toBuffer(), Buffer.from([milestoneIndex])],
+      this.program.programId
+    );
+
+    return milestoneVote;
+  }
+
+  getMilestoneVoteRecordAddress(milestoneIndex: number, authority: PublicKey) {
+    const [milestoneVoteRecord] = PublicKey.findProgramAddressSync(
+      [
+        MILESTONE_VOTE_SEED,
+        this.getMilestoneVoteAddress(milestoneIndex).toBuffer(),
+        authority.toBuffer(),
+      ],
+      this.program.programId
+    );
+
+    return milestoneVoteRecord;
+  }
+
+  async castMilestoneVote(milestoneIndex: number, authority: PublicKey) {
+    const [vesting] = this.getVestingData();
+    const investorData = this.dao.getInvestorDataAddress(authority);
+
+    const ix = await this.program.methods
+      .castMilestoneVote(milestoneIndex)
+      .accounts({
+        investor: authority,
+        investmentDao: this.dao.getDaoPda(),
+        investorData,
+        investorFinancialRecord:
+          this.dao.getInvestorFinancialRecord(investorData),
+        vesting,
+        milestoneVote: this.getMilestoneVoteAddress(milestoneIndex),
+        milestoneVoteRecord: this.getMilestoneVoteRecordAddress(
+          milestoneIndex,
+          authority
+        ),
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async relinquishMilestoneVote(milestoneIndex: number, authority: PublicKey) {
+    const ix = await this.program.methods
+      .relinquishMilestoneVote()
+      .accounts({
+        investor: authority,
+        investmentDao: this.dao.getDaoPda(),
+        investorData: this.dao.getInvestorDataAddress(authority),
+        milestoneVote: this.getMilestoneVoteAddress(milestoneIndex),
+        milestoneVoteRecord: this.getMilestoneVoteRecordAddress(
+          milestoneIndex,
+          authority
+        ),
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  async getVesting() {
+    const [vesting] = this.getVestingData();
+
+    return await this.program.account.vesting.fetchNullable(vesting);
+  }
+
+  //Closing is permissionless, rent goes back to member that opened the vote
+  async closeMilestoneVote(milestoneIndex: number, payer: PublicKey) {
+    const milestoneVote = this.getMilestoneVoteAddress(milestoneIndex);
+    const milestoneVoteData = await this.program.account.milestoneVote.fetch(
+      milestoneVote
+    );
+
+    const ix = await this.program.methods
+      .closeMilestoneVote()
+      .accounts({
+        payer,
+        createdBy: milestoneVoteData.createdBy,
+        milestoneVote,
+        vesting: milestoneVoteData.vesting,
+      })
+      .instruction();
+
+    return ix;
+  }
+
+  //Proposal types whose payload is stored in proposal type itself
+  async createPayloadProposal(
+    proposalType: object,
+    remainingAccounts: PublicKey[] = []
+  ) {
+    const { proposalAddress, proposalIndex } = await this.getNewProposalPda();
+    this.proposalAddress = proposalAddress;
+    this.proposalIndex = proposalIndex;
+    const ix = await this.program.methods
+      .createProposal(proposalType, this.name, this.description, null, null, null)
+      .accounts({
+        authority: this.dao.authority.publicKey,
+        investmentDao: this.dao.getDaoPda(),
+        investorData: this.dao.getInvestorDataAddress(
+          this.dao.authority.publicKey
+        ),
+        systemProgram: SystemProgram.programId,
+        proposal: proposalAddress,
+      })
+      .remainingAccounts(
+        remainingAccounts.map((pubkey) => ({
+          isSigner: false,
+          isWritable: false,
+          pubkey,
+        }))
+      )
+      .instruction();
+
+    return ix;
+  }
 }
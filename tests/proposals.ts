@@ -0,0 +1,512 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Connection, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { AaveCraft } from "../target/types/aave_craft";
+import { ProposalType, VoteOption } from "./constants";
+import { expectError, getKeypair, sendAndConfirmTransaction } from "./helpers";
+import { Dao, DaoOptions } from "./models/dao";
+import { Proposal } from "./models/proposal";
+
+describe("proposals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.AaveCraft as Program<AaveCraft>;
+
+  const connection = new Connection("http://localhost:8899", "confirmed");
+
+  async function createDao(options: DaoOptions = {}) {
+    const authority = await getKeypair(connection);
+    const dao = new Dao(authority, program, 20, 51, {
+      membershipPolicy: { open: {} },
+      ...options,
+    });
+
+    await sendAndConfirmTransaction([await dao.createDao()], connection, [
+      authority,
+    ]);
+
+    return dao;
+  }
+
+  async function joinAndDeposit(dao: Dao, member: Keypair, amount: number) {
+    if (!member.publicKey.equals(dao.authority.publicKey)) {
+      await sendAndConfirmTransaction(
+        [await dao.joinDao(member.publicKey)],
+        connection,
+        [member]
+      );
+    }
+
+    await sendAndConfirmTransaction(
+      await dao.depositToDao(amount, member.publicKey),
+      connection,
+      [member]
+    );
+  }
+
+  async function vote(proposal: Proposal, voters: Keypair[]) {
+    for (const voter of voters) {
+      await sendAndConfirmTransaction(
+        [await proposal.castVote(VoteOption.Yes, voter.publicKey)],
+        connection,
+        [voter]
+      );
+    }
+  }
+
+  async function execute(proposal: Proposal, payer: Keypair) {
+    await sendAndConfirmTransaction(
+      [await proposal.executeProposal(payer.publicKey)],
+      connection,
+      [payer]
+    );
+  }
+
+  it("binds votes and withdrawals to DAO of proposal", async () => {
+    const dao = await createDao();
+    const otherDao = await createDao();
+
+    await joinAndDeposit(dao, dao.authority, LAMPORTS_PER_SOL);
+    await joinAndDeposit(otherDao, otherDao.authority, LAMPORTS_PER_SOL);
+
+    const proposal = new Proposal(
+      dao,
+      program,
+      "Withdrawal",
+      "Withdrawal of other DAO",
+      ProposalType.Withdrawal
+    );
+    await sendAndConfirmTransaction(
+      [await proposal.createWithdrawalProposal(LAMPORTS_PER_SOL / 2)],
+      connection,
+      [dao.authority]
+    );
+
+    //Shares of other DAO can't vote on proposal
+    await expectError(
+      sendAndConfirmTransaction(
+        [
+          await proposal.castVote(
+            VoteOption.Yes,
+            otherDao.authority.publicKey,
+            otherDao
+          ),
+        ],
+        connection,
+        [otherDao.authority]
+      ),
+      "InvalidProposalData"
+    );
+
+    await vote(proposal, [dao.authority]);
+    await execute(proposal, dao.authority);
+
+    //Nor claim its withdrawal
+    await expectError(
+      sendAndConfirmTransaction(
+        [
+          await proposal.withdrawMineFunds(
+            otherDao.authority.publicKey,
+            otherDao
+          ),
+        ],
+        connection,
+        [otherDao.authority]
+      ),
+      "InvalidProposalData"
+    );
+
+    const balanceBefore = await connection.getBalance(
+      dao.authority.publicKey
+    );
+    await sendAndConfirmTransaction(
+      [await proposal.withdrawMineFunds(dao.authority.publicKey)],
+      connection,
+      [dao.authority]
+    );
+    expect(
+      (await connection.getBalance(dao.authority.publicKey)) - balanceBefore
+    ).to.be.greaterThan(0);
+  });
+
+  it("lets only DAO authority veto succeded proposal inside veto window", async () => {
+    const dao = await createDao({ vetoWindow: 30 });
+    const member = await getKeypair(connection);
+
+    await joinAndDeposit(dao, dao.authority, LAMPORTS_PER_SOL);
+
+    const proposal = new Proposal(
+      dao,
+      program,
+      "Withdrawal",
+      "Withdrawal that gets vetoed",
+      ProposalType.Withdrawal
+    );
+    await sendAndConfirmTransaction(
+      [await proposal.createWithdrawalProposal(LAMPORTS_PER_SOL / 2)],
+      connection,
+      [dao.authority]
+    );
+    await vote(proposal, [dao.authority]);
+
+    expect((await proposal.getProposal()).proposalState).to.deep.equal({
+      succeded: {},
+    });
+
+    await expectError(execute(proposal, dao.authority), "VetoWindowNotPassed");
+
+    await expectError(
+      sendAndConfirmTransaction(
+        [await proposal.cancelProposal(member.publicKey)],
+        connection,
+        [member]
+      ),
+      "InvalidDaoAuthority"
+    );
+
+    await sendAndConfirmTransaction(
+      [await proposal.cancelProposal(dao.authority.publicKey)],
+      connection,
+      [dao.authority]
+    );
+
+    expect(
+      await program.account.proposal.fetchNullable(proposal.proposalAddress)
+    ).to.equal(null);
+  });
+
+  it("revokes vesting back to treasury", async () => {
+    const dao = await createDao();
+    const receiver = await getKeypair(connection);
+
+    await joinAndDeposit(dao, dao.authority, 2 * LAMPORTS_PER_SOL);
+
+    const investment = new Proposal(
+      dao,
+      program,
+      "Investment",
+      "Investment that gets revoked",
+      ProposalType.Investing
+    );
+    //Nothing unlocks before revocation, so whole amount goes back
+    await sendAndConfirmTransaction(
+      [
+        await investment.createInvestingProposal(
+          1000,
+          LAMPORTS_PER_SOL,
+          receiver.publicKey,
+          LAMPORTS_PER_SOL,
+          1000
+        ),
+      ],
+      connection,
+      [dao.authority]
+    );
+    await vote(investment, [dao.authority]);
+    await execute(investment, dao.authority);
+
+    const [vesting] = investment.getVestingData();
+    expect((await dao.getDao()).investedAmount.toNumber()).to.equal(
+      LAMPORTS_PER_SOL
+    );
+
+    const treasuryBefore = await connection.getBalance(
+      dao.getDaoTreasuryAddress()
+    );
+
+    const revoke = new Proposal(
+      dao,
+      program,
+      "Revoke",
+      "Revoke investment",
+      ProposalType.RevokeVesting
+    );
+    await sendAndConfirmTransaction(
+      [await revoke.createRevokeVestingProposal(vesting)],
+      connection,
+      [dao.authority]
+    );
+    await vote(revoke, [dao.authority]);
+    await execute(revoke, dao.authority);
+
+    expect(
+      (await connection.getBalance(dao.getDaoTreasuryAddress())) -
+        treasuryBefore
+    ).to.equal(LAMPORTS_PER_SOL);
+    expect((await dao.getDao()).investedAmount.toNumber()).to.equal(0);
+    expect(await investment.getVesting()).to.equal(null);
+  });
+
+  it("approves member vote milestone once threshold of shares approves", async () => {
+    const dao = await createDao();
+    const member1 = await getKeypair(connection);
+    const member2 = await getKeypair(connection);
+    const receiver = await getKeypair(connection);
+
+    await joinAndDeposit(dao, dao.authority, LAMPORTS_PER_SOL);
+    await joinAndDeposit(dao, member1, LAMPORTS_PER_SOL);
+    await joinAndDeposit(dao, member2, LAMPORTS_PER_SOL);
+
+    const investment = new Proposal(
+      dao,
+      program,
+      "Milestone investment",
+      "Investment unlocked by milestones",
+      ProposalType.Investing
+    );
+    await sendAndConfirmTransaction(
+      [
+        await investment.createMilestoneInvestingProposal(receiver.publicKey, [
+          LAMPORTS_PER_SOL / 2,
+          LAMPORTS_PER_SOL / 2,
+        ]),
+      ],
+      connection,
+      [dao.authority]
+    );
+    await vote(investment, [dao.authority, member1]);
+    await execute(investment, dao.authority);
+
+    const [vesting] = investment.getVestingData();
+
+    //Member vote milestones can't be approved through authority proposal
+    const approval = new Proposal(
+      dao,
+      program,
+      "Approve milestone",
+      "Authority approval",
+      ProposalType.ApproveMilestone
+    );
+    await expectError(
+      sendAndConfirmTransaction(
+        [
+          await approval.createPayloadProposal(
+            { approveMilestone: { vesting, milestoneIndex: 0 } },
+            [vesting]
+          ),
+        ],
+        connection,
+        [dao.authority]
+      ),
+      "DaoNotGoverned"
+    );
+
+    await sendAndConfirmTransaction(
+      [await investment.castMilestoneVote(0, member1.publicKey)],
+      connection,
+      [member1]
+    );
+
+    //Third of shares is below threshold
+    const milestoneVote = await program.account.milestoneVote.fetch(
+      investment.getMilestoneVoteAddress(0)
+    );
+    expect(milestoneVote.approvalsWeight.toNumber()).to.equal(
+      LAMPORTS_PER_SOL
+    );
+    expect(milestoneVote.maxVoterWeight.toNumber()).to.equal(
+      3 * LAMPORTS_PER_SOL
+    );
+    expect(
+      (await investment.getVesting()).config.schedule.milestones.milestones[0]
+        .approvedAt
+    ).to.equal(null);
+
+    await sendAndConfirmTransaction(
+      [await investment.castMilestoneVote(0, member2.publicKey)],
+      connection,
+      [member2]
+    );
+
+    expect(
+      (await investment.getVesting()).config.schedule.milestones.milestones[0]
+        .approvedAt
+    ).to.not.equal(null);
+
+    await expectError(
+      sendAndConfirmTransaction(
+        [await investment.castMilestoneVote(0, dao.authority.publicKey)],
+        connection,
+        [dao.authority]
+      ),
+      "MilestoneAlreadyApproved"
+    );
+
+    //Approval keeps member from ragequit until relinquished
+    expect(
+      (await dao.getInvestorData(member2.publicKey)).supportingVotesCount
+    ).to.equal(1);
+    await sendAndConfirmTransaction(
+      [await investment.relinquishMilestoneVote(0, member2.publicKey)],
+      connection,
+      [member2]
+    );
+    expect(
+      (await dao.getInvestorData(member2.publicKey)).supportingVotesCount
+    ).to.equal(0);
+
+    //Unresolved vote of second milestone can't be closed
+    await sendAndConfirmTransaction(
+      [await investment.castMilestoneVote(1, member2.publicKey)],
+      connection,
+      [member2]
+    );
+    await expectError(
+      sendAndConfirmTransaction(
+        [await investment.closeMilestoneVote(1, receiver.publicKey)],
+        connection,
+        [receiver]
+      ),
+      "MilestoneVoteNotResolved"
+    );
+
+    await sendAndConfirmTransaction(
+      [await investment.closeMilestoneVote(0, receiver.publicKey)],
+      connection,
+      [receiver]
+    );
+    expect(
+      await program.account.milestoneVote.fetchNullable(
+        investment.getMilestoneVoteAddress(0)
+      )
+    ).to.equal(null);
+
+    //Approval of first voter is released even though vote is already closed
+    await sendAndConfirmTransaction(
+      [await investment.relinquishMilestoneVote(0, member1.publicKey)],
+      connection,
+      [member1]
+    );
+
+    const balanceBefore = await connection.getBalance(receiver.publicKey);
+    await sendAndConfirmTransaction(
+      [await investment.claimVestedTokens(receiver.publicKey)],
+      connection,
+      [receiver]
+    );
+    expect(
+      (await connection.getBalance(receiver.publicKey)) - balanceBefore
+    ).to.be.greaterThan(LAMPORTS_PER_SOL / 2 - 10_000);
+    expect((await investment.getVesting()).remainingAmount.toNumber()).to.equal(
+      LAMPORTS_PER_SOL / 2
+    );
+  });
+
+  it("withdraws and pays out registered treasury asset", async () => {
+    const dao = await createDao();
+    const member = await getKeypair(connection);
+
+    await joinAndDeposit(dao, dao.authority, LAMPORTS_PER_SOL);
+    await joinAndDeposit(dao, member, LAMPORTS_PER_SOL);
+
+    const mint = await createMint(
+      connection,
+      dao.authority,
+      dao.authority.publicKey,
+      null,
+      0
+    );
+
+    await sendAndConfirmTransaction(
+      [await dao.registerTreasuryAsset(mint)],
+      connection,
+      [dao.authority]
+    );
+    await mintTo(
+      connection,
+      dao.authority,
+      mint,
+      dao.getDaoTreasuryAddress(mint),
+      dao.authority,
+      1000
+    );
+    await sendAndConfirmTransaction(
+      [await dao.syncTreasuryAsset(mint)],
+      connection,
+      [dao.authority]
+    );
+    expect((await dao.getTreasuryAsset(mint)).balance.toNumber()).to.equal(
+      1000
+    );
+
+    const proposal = new Proposal(
+      dao,
+      program,
+      "Asset withdrawal",
+      "Withdrawal of registered asset",
+      ProposalType.Withdrawal
+    );
+    await sendAndConfirmTransaction(
+      [await proposal.createWithdrawalProposal(400, mint)],
+      connection,
+      [dao.authority]
+    );
+    await vote(proposal, [dao.authority, member]);
+    await execute(proposal, dao.authority);
+
+    const treasuryAsset = await dao.getTreasuryAsset(mint);
+    expect(treasuryAsset.balance.toNumber()).to.equal(600);
+    expect(treasuryAsset.totalWithdrawn.toNumber()).to.equal(400);
+
+    const memberTokenAccount = await createAssociatedTokenAccount(
+      connection,
+      member,
+      mint,
+      member.publicKey
+    );
+    await createAssociatedTokenAccount(
+      connection,
+      dao.authority,
+      mint,
+      dao.authority.publicKey
+    );
+
+    for (const investor of [dao.authority, member]) {
+      await sendAndConfirmTransaction(
+        [await proposal.withdrawMineFunds(investor.publicKey)],
+        connection,
+        [investor]
+      );
+    }
+
+    expect(
+      Number((await getAccount(connection, memberTokenAccount)).amount)
+    ).to.equal(200);
+
+    await sendAndConfirmTransaction(
+      [await proposal.relinquishVote(member.publicKey)],
+      connection,
+      [member]
+    );
+
+    //Every registered asset has to be paid out on exit
+    await expectError(
+      sendAndConfirmTransaction(
+        [await dao.ragequit(member.publicKey)],
+        connection,
+        [member]
+      ),
+      "MissingTreasuryAssets"
+    );
+
+    await sendAndConfirmTransaction(
+      [await dao.ragequit(member.publicKey, [mint])],
+      connection,
+      [member]
+    );
+
+    expect(
+      Number((await getAccount(connection, memberTokenAccount)).amount)
+    ).to.equal(500);
+    expect((await dao.getTreasuryAsset(mint)).balance.toNumber()).to.equal(
+      300
+    );
+  });
+});